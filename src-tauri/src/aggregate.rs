@@ -0,0 +1,237 @@
+use chrono::{DateTime, Local, LocalResult, Offset, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataRow;
+use crate::series::{extract_series, SeriesQuery};
+use crate::stats::percentile;
+
+/// Shortest bucket accepted; anything finer is what the raw series is for.
+const MIN_BUCKET_MS: i64 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Aggregator {
+    Mean,
+    Min,
+    Max,
+    /// Last sample of the bucket.
+    Last,
+    P95,
+}
+
+impl Aggregator {
+    fn label(self) -> &'static str {
+        match self {
+            Aggregator::Mean => "mean",
+            Aggregator::Min => "min",
+            Aggregator::Max => "max",
+            Aggregator::Last => "last",
+            Aggregator::P95 => "p95",
+        }
+    }
+}
+
+fn default_aggregators() -> Vec<Aggregator> {
+    vec![Aggregator::Mean]
+}
+
+/// Bucket aggregation request: sensors, optional time window (unix ms, inclusive), bucket
+/// length and the aggregators computed for every sensor.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateQuery {
+    pub sensors: Vec<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub bucket_ms: i64,
+    #[serde(default = "default_aggregators")]
+    pub aggregators: Vec<Aggregator>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateColumn {
+    pub sensor: String,
+    pub aggregator: Aggregator,
+}
+
+/// One bucket `[start, end)`; `values` has one entry per [`AggregateTable::columns`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateBucket {
+    pub start: i64,
+    pub end: i64,
+    /// Logged rows in the bucket.
+    pub rows: usize,
+    pub values: Vec<Option<f64>>,
+}
+
+/// Buckets without any logged row (e.g. between merged logs) are left out.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateTable {
+    pub bucket_ms: i64,
+    pub columns: Vec<AggregateColumn>,
+    pub buckets: Vec<AggregateBucket>,
+}
+
+/// Start of the bucket holding `ts` at a fixed UTC offset. Buckets are aligned to wall-clock
+/// time, so minute and hour buckets start on the minute and the hour.
+pub fn bucket_start(ts: i64, bucket_ms: i64, utc_offset_ms: i64) -> i64 {
+    (ts + utc_offset_ms).div_euclid(bucket_ms) * bucket_ms - utc_offset_ms
+}
+
+fn local_offset_ms(ts: i64) -> i64 {
+    Local
+        .timestamp_millis_opt(ts)
+        .single()
+        .map(|t| t.offset().fix().local_minus_utc() as i64 * 1000)
+        .unwrap_or(0)
+}
+
+/// Start of the local wall-clock bucket holding `ts`, e.g. local midnight for day buckets.
+///
+/// The offset is looked up for the bucket boundary rather than for `ts`, so logs crossing a
+/// DST change keep their buckets on the local hour and day. An hour repeated when clocks go
+/// back forms two buckets.
+pub fn local_bucket_start(ts: i64, bucket_ms: i64) -> i64 {
+    let offset = local_offset_ms(ts);
+    let wall = (ts + offset).div_euclid(bucket_ms) * bucket_ms;
+    let Some(naive) = DateTime::from_timestamp_millis(wall).map(|t| t.naive_utc()) else {
+        return wall - offset;
+    };
+    match Local.from_local_datetime(&naive) {
+        LocalResult::Single(t) => t.timestamp_millis(),
+        // The wall time occurs twice: take the occurrence `ts` belongs to.
+        LocalResult::Ambiguous(a, b) => {
+            let (a, b) = (a.timestamp_millis(), b.timestamp_millis());
+            [a, b].into_iter().filter(|t| *t <= ts).max().unwrap_or(a.min(b))
+        }
+        // Skipped when clocks went forward: the bucket starts where the wall clock resumes,
+        // which is the boundary read with the offset from before the change.
+        LocalResult::None => wall - local_offset_ms(wall - offset),
+    }
+}
+
+/// End of the local bucket starting at `start`: usually `start + bucket_ms`, but a day bucket
+/// on a DST change lasts 23 or 25 hours.
+pub fn local_bucket_end(start: i64, bucket_ms: i64) -> i64 {
+    let nominal = start + bucket_ms;
+    if local_bucket_start(nominal - 1, bucket_ms) == start && local_bucket_start(nominal, bucket_ms) != start {
+        return nominal;
+    }
+    // Search for the first instant in another bucket; DST shifts are at most two hours.
+    let (mut lo, mut hi) = (start + 1, nominal + 3 * 3_600_000);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if local_bucket_start(mid, bucket_ms) == start {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Aggregate the samples (in time order) of one bucket.
+pub fn aggregate(values: &[f64], aggregator: Aggregator) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    match aggregator {
+        Aggregator::Mean => Some(values.iter().sum::<f64>() / values.len() as f64),
+        Aggregator::Min => values.iter().copied().reduce(f64::min),
+        Aggregator::Max => values.iter().copied().reduce(f64::max),
+        Aggregator::Last => values.last().copied(),
+        Aggregator::P95 => {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            percentile(&sorted, 95.0)
+        }
+    }
+}
+
+pub fn aggregate_buckets(rows: &[DataRow], query: &AggregateQuery) -> Result<AggregateTable, String> {
+    if query.bucket_ms < MIN_BUCKET_MS {
+        return Err(format!("时间桶长度不能小于 {} 秒", MIN_BUCKET_MS / 1000));
+    }
+    let aggregators = if query.aggregators.is_empty() {
+        default_aggregators()
+    } else {
+        query.aggregators.clone()
+    };
+
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: query.sensors.clone(),
+            start: query.start,
+            end: query.end,
+            ..Default::default()
+        },
+    );
+
+    let columns = set
+        .series
+        .iter()
+        .flat_map(|s| {
+            aggregators.iter().map(|a| AggregateColumn {
+                sensor: s.sensor.clone(),
+                aggregator: *a,
+            })
+        })
+        .collect();
+
+    let mut buckets = Vec::new();
+    let mut from = 0;
+    while from < set.timestamps.len() {
+        let start = local_bucket_start(set.timestamps[from], query.bucket_ms);
+        let end = local_bucket_end(start, query.bucket_ms);
+        let to = from + set.timestamps[from..].partition_point(|ts| *ts < end);
+
+        let mut values = Vec::with_capacity(set.series.len() * aggregators.len());
+        for series in &set.series {
+            let samples: Vec<f64> = series.values[from..to].iter().flatten().copied().collect();
+            values.extend(aggregators.iter().map(|a| aggregate(&samples, *a)));
+        }
+        buckets.push(AggregateBucket {
+            start,
+            end,
+            rows: to - from,
+            values,
+        });
+        from = to;
+    }
+
+    Ok(AggregateTable {
+        bucket_ms: query.bucket_ms,
+        columns,
+        buckets,
+    })
+}
+
+impl AggregateTable {
+    /// Render the table as CSV, one row per bucket and one column per sensor and aggregator.
+    pub fn to_csv(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        let mut header = vec!["Bucket Start".to_string(), "Rows".to_string()];
+        header.extend(
+            self.columns
+                .iter()
+                .map(|c| format!("{} ({})", c.sensor, c.aggregator.label())),
+        );
+        writer.write_record(&header)?;
+
+        for bucket in &self.buckets {
+            let time = chrono::DateTime::from_timestamp_millis(bucket.start)
+                .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            let mut record = vec![time, bucket.rows.to_string()];
+            record.extend(bucket.values.iter().map(|v| v.map(|v| v.to_string()).unwrap_or_default()));
+            writer.write_record(&record)?;
+        }
+
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
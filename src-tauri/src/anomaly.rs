@@ -0,0 +1,410 @@
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::parse_unit;
+use crate::dataset::row_timestamp;
+use crate::models::DataRow;
+use crate::series::{field_at, parse_value, sample_step, sensor_fields};
+use crate::stats::percentile;
+
+/// Fewer samples than this in a rolling window give no usable baseline.
+const MIN_WINDOW_SAMPLES: usize = 5;
+/// Scales a median absolute deviation to the standard deviation of normal data.
+const MAD_SCALE: f64 = 1.4826;
+/// Same for a mean absolute deviation (sqrt(pi / 2)).
+const MEAN_ABS_SCALE: f64 = 1.2533;
+
+fn default_window_ms() -> i64 {
+    60_000
+}
+
+fn default_z_threshold() -> f64 {
+    4.0
+}
+
+fn default_mad_threshold() -> f64 {
+    5.0
+}
+
+fn default_step_threshold() -> f64 {
+    8.0
+}
+
+fn default_max_results() -> usize {
+    500
+}
+
+/// Anomaly scan request. Thresholds are in (robust) standard deviations.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyQuery {
+    /// Sensors to scan; all numeric sensors when absent. `[Yes/No]` flags are never scanned.
+    pub sensors: Option<Vec<String>>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Rolling window for the z-score baseline and the median/MAD.
+    #[serde(default = "default_window_ms")]
+    pub window_ms: i64,
+    #[serde(default = "default_z_threshold")]
+    pub z_threshold: f64,
+    #[serde(default = "default_mad_threshold")]
+    pub mad_threshold: f64,
+    /// Jump between consecutive samples, relative to the typical sample-to-sample change.
+    #[serde(default = "default_step_threshold")]
+    pub step_threshold: f64,
+    /// Values a sensor reports when it drops out, in addition to 0 (e.g. 65535, -1).
+    #[serde(default)]
+    pub sentinels: Vec<f64>,
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+}
+
+impl Default for AnomalyQuery {
+    fn default() -> Self {
+        Self {
+            sensors: None,
+            start: None,
+            end: None,
+            window_ms: default_window_ms(),
+            z_threshold: default_z_threshold(),
+            mad_threshold: default_mad_threshold(),
+            step_threshold: default_step_threshold(),
+            sentinels: Vec::new(),
+            max_results: default_max_results(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnomalyKind {
+    /// Far from the mean of the preceding window.
+    ZScore,
+    /// Far from the median of the surrounding window, in MADs.
+    Mad,
+    /// Sudden jump from the previous sample.
+    Step,
+    /// Reads 0 or a sentinel value while the sensor normally does not.
+    Dropout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    /// From how far the score exceeds its threshold.
+    fn from_ratio(ratio: f64) -> Self {
+        if ratio >= 2.0 {
+            Severity::High
+        } else if ratio >= 1.4 {
+            Severity::Medium
+        } else {
+            Severity::Low
+        }
+    }
+}
+
+/// Consecutive flagged samples of one sensor and kind, reported at their peak.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Anomaly {
+    pub sensor: String,
+    /// Group path, top-level group first.
+    pub path: Vec<String>,
+    pub kind: AnomalyKind,
+    pub severity: Severity,
+    /// Timestamp and value of the most extreme sample.
+    pub timestamp: i64,
+    pub value: f64,
+    /// What the sample was compared against (window mean or median, or the previous sample).
+    pub expected: f64,
+    /// Deviation in (robust) standard deviations; for dropouts, the relative drop.
+    pub score: f64,
+    /// How far the anomaly exceeds its threshold (for dropouts, the gap in sample intervals);
+    /// used to keep the most severe ones.
+    #[serde(skip)]
+    ratio: f64,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyReport {
+    /// Sorted by time.
+    pub anomalies: Vec<Anomaly>,
+    pub scanned_sensors: usize,
+    /// More anomalies were found than `max_results`; the most severe ones are kept.
+    pub truncated: bool,
+}
+
+/// One flagged sample before merging.
+#[derive(Debug, Clone, Copy)]
+struct Hit {
+    index: usize,
+    value: f64,
+    expected: f64,
+    score: f64,
+    /// `score / threshold`, used for severity and ranking.
+    ratio: f64,
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    percentile(values, 50.0)
+}
+
+/// Samples whose |z| against the trailing window `[t - window, t)` exceeds `threshold`.
+fn zscore_hits(samples: &[(i64, f64)], window_ms: i64, threshold: f64) -> Vec<Hit> {
+    // Welford's running mean and sum of squared deviations, updated as samples enter and
+    // leave the window; a plain sum of squares cancels badly on large offsets (e.g. MHz).
+    let (mut mean, mut m2, mut lo) = (0.0, 0.0, 0usize);
+    let mut hits = Vec::new();
+    for (i, &(ts, value)) in samples.iter().enumerate() {
+        while lo < i && samples[lo].0 < ts - window_ms {
+            let old = samples[lo].1;
+            let n = (i - lo - 1) as f64;
+            if n == 0.0 {
+                (mean, m2) = (0.0, 0.0);
+            } else {
+                let prev_mean = mean;
+                mean -= (old - mean) / n;
+                m2 -= (old - prev_mean) * (old - mean);
+            }
+            lo += 1;
+        }
+        let n = i - lo;
+        if n >= MIN_WINDOW_SAMPLES {
+            // Rounding can leave m2 a hair below zero on a flat line.
+            let std = (m2.max(0.0) / n as f64).sqrt();
+            // Relative floor so float noise on a flat line is not an anomaly.
+            if std > 64.0 * f64::EPSILON * mean.abs().max(1.0) {
+                let score = (value - mean).abs() / std;
+                if score > threshold {
+                    hits.push(Hit {
+                        index: i,
+                        value,
+                        expected: mean,
+                        score,
+                        ratio: score / threshold,
+                    });
+                }
+            }
+        }
+        let prev_mean = mean;
+        mean += (value - mean) / (n + 1) as f64;
+        m2 += (value - prev_mean) * (value - mean);
+    }
+    hits
+}
+
+/// Samples further than `threshold` robust deviations from the median of the centred window.
+fn mad_hits(samples: &[(i64, f64)], window_ms: i64, threshold: f64) -> Vec<Hit> {
+    let half = window_ms / 2;
+    let (mut lo, mut hi) = (0usize, 0usize);
+    let mut hits = Vec::new();
+    for (i, &(ts, value)) in samples.iter().enumerate() {
+        while samples[lo].0 < ts - half {
+            lo += 1;
+        }
+        while hi < samples.len() && samples[hi].0 <= ts + half {
+            hi += 1;
+        }
+        if hi - lo < MIN_WINDOW_SAMPLES {
+            continue;
+        }
+        let mut window: Vec<f64> = samples[lo..hi].iter().map(|(_, v)| *v).collect();
+        let Some(center) = median(&mut window) else {
+            continue;
+        };
+        let mut deviations: Vec<f64> = window.iter().map(|v| (v - center).abs()).collect();
+        let mad = median(&mut deviations).unwrap_or(0.0) * MAD_SCALE;
+        if mad <= 0.0 {
+            continue;
+        }
+        let score = (value - center).abs() / mad;
+        if score > threshold {
+            hits.push(Hit {
+                index: i,
+                value,
+                expected: center,
+                score,
+                ratio: score / threshold,
+            });
+        }
+    }
+    hits
+}
+
+/// Jumps between consecutive samples larger than `threshold` times the robust spread of all
+/// sample-to-sample changes.
+fn step_hits(samples: &[(i64, f64)], threshold: f64) -> Vec<Hit> {
+    let diffs: Vec<f64> = samples.windows(2).map(|w| w[1].1 - w[0].1).collect();
+    let mut abs: Vec<f64> = diffs.iter().map(|d| d.abs()).collect();
+    let mut scale = median(&mut abs).unwrap_or(0.0) * MAD_SCALE;
+    if scale <= 0.0 && !abs.is_empty() {
+        // Mostly flat sensors (fixed clocks, idle fans): fall back to the mean absolute change.
+        scale = abs.iter().sum::<f64>() / abs.len() as f64 * MEAN_ABS_SCALE;
+    }
+    if scale <= 0.0 {
+        return Vec::new();
+    }
+    diffs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| {
+            let score = d.abs() / scale;
+            (score > threshold).then(|| Hit {
+                index: i + 1,
+                value: samples[i + 1].1,
+                expected: samples[i].1,
+                score,
+                ratio: score / threshold,
+            })
+        })
+        .collect()
+}
+
+/// Samples reading 0 or a sentinel while the median of the surrounding window does not.
+///
+/// The ratio of a dropout is the time until valid data resumes, in sample intervals, so a
+/// single bad sample is minor and a sensor gone for several samples is severe.
+fn dropout_hits(samples: &[(i64, f64)], window_ms: i64, sentinels: &[f64]) -> Vec<Hit> {
+    let is_sentinel = |v: f64| v == 0.0 || sentinels.contains(&v);
+    let half = window_ms / 2;
+    let (mut lo, mut hi) = (0usize, 0usize);
+    let mut hits = Vec::new();
+    for (i, &(ts, value)) in samples.iter().enumerate() {
+        while samples[lo].0 < ts - half {
+            lo += 1;
+        }
+        while hi < samples.len() && samples[hi].0 <= ts + half {
+            hi += 1;
+        }
+        if !is_sentinel(value) {
+            continue;
+        }
+        let mut window: Vec<f64> = samples[lo..hi].iter().map(|(_, v)| *v).collect();
+        match median(&mut window) {
+            Some(center) if !is_sentinel(center) => hits.push(Hit {
+                index: i,
+                value,
+                expected: center,
+                // Relative drop from the window median.
+                score: ((center - value) / center).abs(),
+                ratio: 0.0,
+            }),
+            _ => {}
+        }
+    }
+
+    let timestamps: Vec<i64> = samples.iter().map(|(ts, _)| *ts).collect();
+    let step = sample_step(&timestamps).max(1);
+    for run in hits.chunk_by_mut(|a, b| b.index == a.index + 1) {
+        let (first, last) = (run[0].index, run[run.len() - 1].index);
+        let resumed = samples.get(last + 1).map_or(samples[last].0 + step, |(ts, _)| *ts);
+        let ratio = (resumed - samples[first].0) as f64 / step as f64;
+        for hit in run {
+            hit.ratio = ratio;
+        }
+    }
+    hits
+}
+
+/// Merge runs of consecutive flagged samples into one anomaly at the strongest sample.
+fn merge_hits(samples: &[(i64, f64)], hits: Vec<Hit>, sensor: &str, path: &[String], kind: AnomalyKind) -> Vec<Anomaly> {
+    let mut runs: Vec<(Hit, usize, usize)> = Vec::new();
+    for hit in hits {
+        match runs.last_mut() {
+            Some((peak, _, last)) if hit.index == *last + 1 => {
+                *last = hit.index;
+                if hit.ratio > peak.ratio {
+                    *peak = hit;
+                }
+            }
+            _ => runs.push((hit, hit.index, hit.index)),
+        }
+    }
+    runs.into_iter()
+        .map(|(peak, first, last)| Anomaly {
+            sensor: sensor.to_string(),
+            path: path.to_vec(),
+            kind,
+            severity: Severity::from_ratio(peak.ratio),
+            timestamp: samples[peak.index].0,
+            value: peak.value,
+            expected: peak.expected,
+            score: peak.score,
+            ratio: peak.ratio,
+            start: samples[first].0,
+            end: samples[last].0,
+        })
+        .collect()
+}
+
+/// Anomalies of one sensor's samples (ascending timestamps, missing values left out).
+pub fn sensor_anomalies(samples: &[(i64, f64)], sensor: &str, path: &[String], query: &AnomalyQuery) -> Vec<Anomaly> {
+    let mut found = Vec::new();
+    for (kind, hits) in [
+        (AnomalyKind::ZScore, zscore_hits(samples, query.window_ms, query.z_threshold)),
+        (AnomalyKind::Mad, mad_hits(samples, query.window_ms, query.mad_threshold)),
+        (AnomalyKind::Step, step_hits(samples, query.step_threshold)),
+        (AnomalyKind::Dropout, dropout_hits(samples, query.window_ms, &query.sentinels)),
+    ] {
+        found.extend(merge_hits(samples, hits, sensor, path, kind));
+    }
+    found
+}
+
+pub fn detect_anomalies(rows: &[DataRow], query: &AnomalyQuery) -> Result<AnomalyReport, String> {
+    if query.window_ms <= 0 {
+        return Err("检测窗口必须大于 0".to_string());
+    }
+    if [query.z_threshold, query.mad_threshold, query.step_threshold]
+        .iter()
+        .any(|t| !t.is_finite() || *t <= 0.0)
+    {
+        return Err("异常检测阈值必须为正数".to_string());
+    }
+
+    let window: Vec<(i64, &DataRow)> = rows
+        .iter()
+        .filter_map(|row| row_timestamp(row).map(|ts| (ts, row)))
+        .filter(|(ts, _)| query.start.is_none_or(|s| *ts >= s) && query.end.is_none_or(|e| *ts <= e))
+        .collect();
+    let fields = rows
+        .first()
+        .map(sensor_fields)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, sensor)| query.sensors.as_ref().is_none_or(|wanted| wanted.contains(sensor)))
+        // Every change of a `[Yes/No]` flag would look like a spike.
+        .filter(|(_, sensor)| parse_unit(sensor).as_deref() != Some("Yes/No"));
+
+    let mut report = AnomalyReport::default();
+    let mut found: Vec<Anomaly> = Vec::new();
+    for (path, sensor) in fields {
+        let samples: Vec<(i64, f64)> = window
+            .iter()
+            .filter_map(|(ts, row)| field_at(row, &path, &sensor).and_then(parse_value).map(|v| (*ts, v)))
+            .collect();
+        if samples.is_empty() {
+            continue;
+        }
+        report.scanned_sensors += 1;
+        found.extend(sensor_anomalies(&samples, &sensor, &path, query));
+    }
+
+    if found.len() > query.max_results {
+        found.sort_by(|a, b| b.ratio.total_cmp(&a.ratio));
+        found.truncate(query.max_results);
+        report.truncated = true;
+    }
+    found.sort_by_key(|a| a.timestamp);
+    report.anomalies = found;
+    Ok(report)
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::models::DataRow;
+use crate::series::{field_at, parse_bool, parse_value, sensor_fields};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValueType {
+    Numeric,
+    /// HWiNFO `[Yes/No]` sensors (e.g. throttling flags).
+    Boolean,
+    Text,
+}
+
+/// One sensor of a dataset, described without shipping its rows.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorInfo {
+    pub sensor: String,
+    /// Group path, top-level group first.
+    pub path: Vec<String>,
+    /// Position of the column in the CSV, when known.
+    pub column: Option<usize>,
+    /// Unit from the trailing `[...]` of the name, e.g. `MHz` for `Core Clock [MHz]`.
+    pub unit: Option<String>,
+    pub value_type: ValueType,
+    /// Rows holding a non-empty value.
+    pub samples: usize,
+    /// Share of rows (0-1) without a value.
+    pub missing_ratio: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Unit in the trailing brackets of a sensor name.
+pub fn parse_unit(name: &str) -> Option<String> {
+    let name = name.trim_end();
+    let inner = name.strip_suffix(']')?;
+    let open = inner.rfind('[')?;
+    let unit = inner[open + 1..].trim();
+    (!unit.is_empty()).then(|| unit.to_string())
+}
+
+/// CSV column of every field in `fields`.
+///
+/// HWiNFO repeats names across devices (e.g. the temperature of each drive), so the nth field
+/// of a name (in `fields` order) gets the nth column of that name. When fewer fields than
+/// columns exist, because duplicates mapped to the same group, the row holds the value of
+/// the last column written, so the fields are matched against the last columns.
+pub fn field_columns(fields: &[(Vec<String>, String)], columns: &[String]) -> Vec<Option<usize>> {
+    let mut positions: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, column) in columns.iter().enumerate() {
+        positions.entry(column.as_str()).or_default().push(i);
+    }
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for (_, sensor) in fields {
+        *totals.entry(sensor.as_str()).or_default() += 1;
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    fields
+        .iter()
+        .map(|(_, sensor)| {
+            let nth = seen.entry(sensor.as_str()).or_default();
+            let index = *nth;
+            *nth += 1;
+            let matching = positions.get(sensor.as_str())?;
+            let skip = matching.len().saturating_sub(totals[sensor.as_str()]);
+            matching.get(skip + index).copied()
+        })
+        .collect()
+}
+
+/// Describe every sensor of `rows`. The layout of the first row defines the sensor list;
+/// `columns` is the CSV header order used to fill in column indices.
+pub fn build_catalog(rows: &[DataRow], columns: &[String]) -> Vec<SensorInfo> {
+    let Some(first) = rows.first() else {
+        return Vec::new();
+    };
+
+    let fields = sensor_fields(first);
+    let field_columns = field_columns(&fields, columns);
+    fields
+        .into_iter()
+        .zip(field_columns)
+        .map(|((path, sensor), column)| {
+            let mut samples = 0usize;
+            let mut numeric = 0usize;
+            let mut boolean = 0usize;
+            let mut min: Option<f64> = None;
+            let mut max: Option<f64> = None;
+
+            for raw in rows.iter().filter_map(|row| field_at(row, &path, &sensor)) {
+                if raw.trim().is_empty() {
+                    continue;
+                }
+                samples += 1;
+                if parse_bool(raw).is_some() {
+                    boolean += 1;
+                } else if parse_value(raw).is_some() {
+                    numeric += 1;
+                }
+                if let Some(v) = parse_value(raw) {
+                    min = Some(min.map_or(v, |m| m.min(v)));
+                    max = Some(max.map_or(v, |m| m.max(v)));
+                }
+            }
+
+            let unit = parse_unit(&sensor);
+            let value_type = if unit.as_deref() == Some("Yes/No") {
+                ValueType::Boolean
+            } else if samples > 0 && numeric == samples {
+                ValueType::Numeric
+            } else if samples > 0 && boolean == samples {
+                ValueType::Boolean
+            } else if samples == 0 {
+                // Never written: treat as numeric so it still charts (as a gap).
+                ValueType::Numeric
+            } else {
+                ValueType::Text
+            };
+            if value_type == ValueType::Text {
+                min = None;
+                max = None;
+            }
+
+            SensorInfo {
+                column,
+                unit,
+                value_type,
+                samples,
+                missing_ratio: 1.0 - samples as f64 / rows.len() as f64,
+                min,
+                max,
+                sensor,
+                path,
+            }
+        })
+        .collect()
+}
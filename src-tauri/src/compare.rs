@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::row_timestamp;
+use crate::downsample::Downsample;
+use crate::models::DataRow;
+use crate::resample::ResampleSpec;
+use crate::series::{extract_series, sensor_fields, SeriesQuery};
+use crate::stats::{describe, welch_t_test, SensorStats, WelchTest};
+use crate::thresholds::Comparison;
+
+/// p-value below which a difference is reported as significant.
+const SIGNIFICANCE: f64 = 0.05;
+
+/// Moment both runs are aligned on ("elapsed 0").
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum Alignment {
+    /// First sample of each run.
+    #[default]
+    Start,
+    /// First sample where `sensor op threshold` holds, e.g. CPU load > 80 % for "load start".
+    SyncEvent {
+        sensor: String,
+        op: Comparison,
+        threshold: f64,
+    },
+}
+
+/// A/B comparison request. Run A is the baseline; deltas are `B - A`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareQuery {
+    #[serde(default)]
+    pub alignment: Alignment,
+    /// Length of the compared window after the alignment point; defaults to the shorter run.
+    pub duration_ms: Option<i64>,
+    /// Sensors (names from run A) to compare, on every device logging them; all sensors when absent.
+    pub sensors: Option<Vec<String>>,
+    /// Return side-by-side series for the compared sensors.
+    #[serde(default)]
+    pub include_series: bool,
+    pub max_points: Option<usize>,
+    /// Resample both runs onto the same elapsed-time grid, so runs logged at different
+    /// polling intervals weigh equally in the statistics and line up sample by sample.
+    #[serde(default)]
+    pub resample: Option<ResampleSpec>,
+}
+
+/// One run of a sensor on the elapsed-time axis (ms since the alignment point).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElapsedSeries {
+    pub elapsed_ms: Vec<i64>,
+    pub values: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorComparison {
+    pub sensor_a: String,
+    pub sensor_b: String,
+    /// Group paths of both sensors, telling apart devices that log the same name.
+    pub path_a: Vec<String>,
+    pub path_b: Vec<String>,
+    pub a: SensorStats,
+    pub b: SensorStats,
+    /// `mean(B) - mean(A)`.
+    pub delta_mean: Option<f64>,
+    /// Delta relative to `|mean(A)|`, in percent.
+    pub delta_percent: Option<f64>,
+    pub welch: Option<WelchTest>,
+    pub significant: bool,
+    pub series_a: Option<ElapsedSeries>,
+    pub series_b: Option<ElapsedSeries>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonReport {
+    /// Alignment points (unix ms) of both runs.
+    pub origin_a: i64,
+    pub origin_b: i64,
+    pub duration_ms: i64,
+    pub sensors: Vec<SensorComparison>,
+    /// Sensors of one run without a counterpart in the other.
+    pub unmatched_a: Vec<String>,
+    pub unmatched_b: Vec<String>,
+}
+
+/// Name used to pair sensors across runs: case-insensitive, whitespace-insensitive.
+pub fn canonical_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn bounds(rows: &[DataRow]) -> Option<(i64, i64)> {
+    let first = rows.iter().find_map(row_timestamp)?;
+    let last = rows.iter().rev().find_map(row_timestamp)?;
+    Some((first, last))
+}
+
+fn origin(rows: &[DataRow], alignment: &Alignment) -> Option<i64> {
+    match alignment {
+        Alignment::Start => bounds(rows).map(|(first, _)| first),
+        Alignment::SyncEvent { sensor, op, threshold } => {
+            let set = extract_series(
+                rows,
+                &SeriesQuery {
+                    sensors: vec![sensor.clone()],
+                    ..Default::default()
+                },
+            );
+            set.timestamps
+                .iter()
+                .zip(&set.series[0].values)
+                .find(|(_, v)| v.is_some_and(|v| op.holds(v, *threshold)))
+                .map(|(ts, _)| *ts)
+        }
+    }
+}
+
+/// A sensor field as `(group path, field name)`.
+pub type SensorField = (Vec<String>, String);
+
+/// Fields of run A to compare: every device logging one of the requested names, or all
+/// sensors. Requested names not logged in run A are returned separately.
+fn fields_a(rows: &[DataRow], sensors: Option<&[String]>) -> (Vec<SensorField>, Vec<String>) {
+    let fields = rows.first().map(sensor_fields).unwrap_or_default();
+    let Some(sensors) = sensors else {
+        return (fields, Vec::new());
+    };
+    let mut picked = Vec::new();
+    let mut missing = Vec::new();
+    for sensor in sensors {
+        let mut found = false;
+        for field in fields.iter().filter(|(_, name)| name == sensor) {
+            found = true;
+            if !picked.contains(field) {
+                picked.push(field.clone());
+            }
+        }
+        if !found {
+            missing.push(sensor.clone());
+        }
+    }
+    (picked, missing)
+}
+
+/// Pair sensors of both runs, in the order of run A: on group path and canonical name
+/// first, then on canonical name alone for sensors whose device moved to another group.
+pub fn pair_sensors(
+    fields_a: &[SensorField],
+    fields_b: &[SensorField],
+) -> (Vec<(SensorField, SensorField)>, Vec<SensorField>, Vec<SensorField>) {
+    let canonical_a: Vec<String> = fields_a.iter().map(|(_, n)| canonical_name(n)).collect();
+    let canonical_b: Vec<String> = fields_b.iter().map(|(_, n)| canonical_name(n)).collect();
+    let mut used = vec![false; fields_b.len()];
+    let mut matched: Vec<Option<usize>> = vec![None; fields_a.len()];
+
+    for (i, (path, _)) in fields_a.iter().enumerate() {
+        let found = (0..fields_b.len()).find(|&j| !used[j] && fields_b[j].0 == *path && canonical_b[j] == canonical_a[i]);
+        if let Some(j) = found {
+            used[j] = true;
+            matched[i] = Some(j);
+        }
+    }
+    for i in 0..fields_a.len() {
+        if matched[i].is_some() {
+            continue;
+        }
+        if let Some(j) = (0..fields_b.len()).find(|&j| !used[j] && canonical_b[j] == canonical_a[i]) {
+            used[j] = true;
+            matched[i] = Some(j);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let mut unmatched_a = Vec::new();
+    for (field, j) in fields_a.iter().zip(&matched) {
+        match j {
+            Some(j) => pairs.push((field.clone(), fields_b[*j].clone())),
+            None => unmatched_a.push(field.clone()),
+        }
+    }
+    let unmatched_b = fields_b
+        .iter()
+        .zip(&used)
+        .filter(|(_, used)| !**used)
+        .map(|(f, _)| f.clone())
+        .collect();
+    (pairs, unmatched_a, unmatched_b)
+}
+
+fn elapsed_series(rows: &[DataRow], field: &SensorField, origin: i64, duration_ms: i64, query: &CompareQuery) -> ElapsedSeries {
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: vec![field.1.clone()],
+            paths: vec![Some(field.0.clone())],
+            start: Some(origin),
+            end: Some(origin + duration_ms),
+            max_points: query.max_points,
+            downsample: Downsample::Lttb,
+            resample: query.resample,
+            ..Default::default()
+        },
+    );
+    ElapsedSeries {
+        elapsed_ms: set.timestamps.iter().map(|ts| ts - origin).collect(),
+        values: set.series.into_iter().next().map(|s| s.values).unwrap_or_default(),
+    }
+}
+
+/// Compare run B against baseline run A over the same elapsed window.
+pub fn compare_runs(rows_a: &[DataRow], rows_b: &[DataRow], query: &CompareQuery) -> Result<ComparisonReport, String> {
+    let (origin_a, origin_b) = match (origin(rows_a, &query.alignment), origin(rows_b, &query.alignment)) {
+        (Some(a), Some(b)) => (a, b),
+        _ if query.alignment == Alignment::Start => return Err("数据集中没有带时间戳的行".to_string()),
+        _ => return Err("未在两个数据集中都找到同步事件".to_string()),
+    };
+    let (_, last_a) = bounds(rows_a).unwrap_or((origin_a, origin_a));
+    let (_, last_b) = bounds(rows_b).unwrap_or((origin_b, origin_b));
+    let duration_ms = query
+        .duration_ms
+        .unwrap_or_else(|| (last_a - origin_a).min(last_b - origin_b))
+        .max(0);
+
+    let (fields_a, missing_a) = fields_a(rows_a, query.sensors.as_deref());
+    let fields_b = rows_b.first().map(sensor_fields).unwrap_or_default();
+    let (pairs, unmatched_a, unmatched_b) = pair_sensors(&fields_a, &fields_b);
+
+    let window = |rows: &[DataRow], origin: i64, fields: Vec<&SensorField>| {
+        extract_series(
+            rows,
+            &SeriesQuery {
+                sensors: fields.iter().map(|(_, name)| name.clone()).collect(),
+                paths: fields.iter().map(|(path, _)| Some(path.clone())).collect(),
+                start: Some(origin),
+                end: Some(origin + duration_ms),
+                resample: query.resample,
+                ..Default::default()
+            },
+        )
+    };
+    let set_a = window(rows_a, origin_a, pairs.iter().map(|(a, _)| a).collect());
+    let set_b = window(rows_b, origin_b, pairs.iter().map(|(_, b)| b).collect());
+
+    let mut sensors = Vec::new();
+    for ((series_a, series_b), (field_a, field_b)) in set_a.series.iter().zip(&set_b.series).zip(&pairs) {
+        let a = describe(&field_a.1, &set_a.timestamps, &series_a.values, &[]);
+        let b = describe(&field_b.1, &set_b.timestamps, &series_b.values, &[]);
+        // Text sensors and sensors never written in one of the runs.
+        if a.count == 0 || b.count == 0 {
+            continue;
+        }
+
+        let values_a: Vec<f64> = series_a.values.iter().flatten().copied().collect();
+        let values_b: Vec<f64> = series_b.values.iter().flatten().copied().collect();
+        let welch = welch_t_test(&values_a, &values_b);
+        let delta_mean = a.mean.zip(b.mean).map(|(a, b)| b - a);
+        let delta_percent = a
+            .mean
+            .zip(delta_mean)
+            .filter(|(mean, _)| *mean != 0.0)
+            .map(|(mean, delta)| delta / mean.abs() * 100.0);
+
+        let (series_a, series_b) = if query.include_series {
+            (
+                Some(elapsed_series(rows_a, field_a, origin_a, duration_ms, query)),
+                Some(elapsed_series(rows_b, field_b, origin_b, duration_ms, query)),
+            )
+        } else {
+            (None, None)
+        };
+
+        sensors.push(SensorComparison {
+            sensor_a: field_a.1.clone(),
+            sensor_b: field_b.1.clone(),
+            path_a: field_a.0.clone(),
+            path_b: field_b.0.clone(),
+            significant: welch.is_some_and(|w| w.p_value < SIGNIFICANCE),
+            a,
+            b,
+            delta_mean,
+            delta_percent,
+            welch,
+            series_a,
+            series_b,
+        });
+    }
+
+    Ok(ComparisonReport {
+        origin_a,
+        origin_b,
+        duration_ms,
+        sensors,
+        unmatched_a: missing_a.into_iter().chain(unmatched_a.into_iter().map(|(_, name)| name)).collect(),
+        unmatched_b: unmatched_b.into_iter().map(|(_, name)| name).collect(),
+    })
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataRow;
+use crate::series::{extract_series, sample_step, SeriesQuery};
+
+/// Pairs with fewer complete samples than this get no coefficient.
+const MIN_PAIRS: usize = 3;
+
+fn default_max_lag_ms() -> i64 {
+    120_000
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CorrelationMethod {
+    #[default]
+    Pearson,
+    /// Pearson on ranks: picks up monotonic but non-linear relations (e.g. fan curves).
+    Spearman,
+}
+
+/// Correlation matrix request over an optional time window (unix ms, inclusive).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrelationQuery {
+    pub sensors: Vec<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    #[serde(default)]
+    pub method: CorrelationMethod,
+}
+
+/// Symmetric matrix; `matrix[i][j]` relates `sensors[i]` and `sensors[j]`, computed on the rows
+/// where both have a value (`samples[i][j]`). `None` when there are too few pairs or a sensor
+/// is constant.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrelationMatrix {
+    pub sensors: Vec<String>,
+    pub method: CorrelationMethod,
+    pub matrix: Vec<Vec<Option<f64>>>,
+    pub samples: Vec<Vec<usize>>,
+}
+
+/// Cross-correlation request: how `follower` trails `leader`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LagQuery {
+    pub leader: String,
+    pub follower: String,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Largest shift tried in either direction.
+    #[serde(default = "default_max_lag_ms")]
+    pub max_lag_ms: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LagPoint {
+    pub lag_ms: i64,
+    pub correlation: f64,
+}
+
+/// Pearson correlation of the leader against the follower shifted by each lag. A positive
+/// `best_lag_ms` means the follower reacts that much later than the leader.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LagResult {
+    pub leader: String,
+    pub follower: String,
+    /// Sample interval the lags are multiples of.
+    pub step_ms: i64,
+    pub best_lag_ms: Option<i64>,
+    pub best_correlation: Option<f64>,
+    pub lags: Vec<LagPoint>,
+}
+
+/// Pearson correlation coefficient; `None` for fewer than [`MIN_PAIRS`] pairs or zero variance.
+pub fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n < MIN_PAIRS {
+        return None;
+    }
+    let mean_x = xs[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = ys[..n].iter().sum::<f64>() / n as f64;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs[..n].iter().zip(&ys[..n]) {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+    if var_x <= 0.0 || var_y <= 0.0 {
+        return None;
+    }
+    Some((cov / (var_x * var_y).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// 1-based ranks; ties get the average of the ranks they span.
+pub fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &k in &order[i..=j] {
+            ranks[k] = rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+pub fn spearman(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    pearson(&ranks(xs), &ranks(ys))
+}
+
+/// Rows where both series have a value.
+fn complete_pairs(a: &[Option<f64>], b: &[Option<f64>]) -> (Vec<f64>, Vec<f64>) {
+    a.iter()
+        .zip(b)
+        .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+        .unzip()
+}
+
+pub fn correlation_matrix(rows: &[DataRow], query: &CorrelationQuery) -> CorrelationMatrix {
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: query.sensors.clone(),
+            start: query.start,
+            end: query.end,
+            ..Default::default()
+        },
+    );
+
+    let n = set.series.len();
+    let mut matrix = vec![vec![None; n]; n];
+    let mut samples = vec![vec![0usize; n]; n];
+    for i in 0..n {
+        for j in i..n {
+            let (xs, ys) = complete_pairs(&set.series[i].values, &set.series[j].values);
+            let r = match query.method {
+                CorrelationMethod::Pearson => pearson(&xs, &ys),
+                CorrelationMethod::Spearman => spearman(&xs, &ys),
+            };
+            matrix[i][j] = r;
+            matrix[j][i] = r;
+            samples[i][j] = xs.len();
+            samples[j][i] = xs.len();
+        }
+    }
+
+    CorrelationMatrix {
+        sensors: query.sensors.clone(),
+        method: query.method,
+        matrix,
+        samples,
+    }
+}
+
+/// Correlate `leader[i]` with `follower[i + lag]` for every lag within `max_lag` samples.
+pub fn cross_correlation(leader: &[Option<f64>], follower: &[Option<f64>], max_lag: usize) -> Vec<(i64, f64)> {
+    let n = leader.len().min(follower.len());
+    let max_lag = max_lag.min(n.saturating_sub(MIN_PAIRS)) as i64;
+    (-max_lag..=max_lag)
+        .filter_map(|lag| {
+            let (a, b) = if lag >= 0 {
+                (&leader[..n - lag as usize], &follower[lag as usize..n])
+            } else {
+                (&leader[(-lag) as usize..n], &follower[..n - (-lag) as usize])
+            };
+            let (xs, ys) = complete_pairs(a, b);
+            pearson(&xs, &ys).map(|r| (lag, r))
+        })
+        .collect()
+}
+
+/// Estimate the delay between two sensors. Assumes a roughly constant logging interval.
+pub fn lag_analysis(rows: &[DataRow], query: &LagQuery) -> LagResult {
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: vec![query.leader.clone(), query.follower.clone()],
+            start: query.start,
+            end: query.end,
+            ..Default::default()
+        },
+    );
+    let step_ms = sample_step(&set.timestamps);
+    let max_lag = if step_ms > 0 {
+        (query.max_lag_ms.max(0) / step_ms) as usize
+    } else {
+        0
+    };
+
+    let lags: Vec<LagPoint> = cross_correlation(&set.series[0].values, &set.series[1].values, max_lag)
+        .into_iter()
+        .map(|(lag, correlation)| LagPoint {
+            lag_ms: lag * step_ms,
+            correlation,
+        })
+        .collect();
+    // Strongest positive response; on ties the smallest shift wins.
+    let best = lags
+        .iter()
+        .copied()
+        .reduce(|a, b| {
+            if b.correlation > a.correlation || (b.correlation == a.correlation && b.lag_ms.abs() < a.lag_ms.abs()) {
+                b
+            } else {
+                a
+            }
+        });
+
+    LagResult {
+        leader: query.leader.clone(),
+        follower: query.follower.clone(),
+        step_ms,
+        best_lag_ms: best.map(|p| p.lag_ms),
+        best_correlation: best.map(|p| p.correlation),
+        lags,
+    }
+}
@@ -1,15 +1,15 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Cursor};
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
 
 use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use chrono::offset::LocalResult;
 use csv::{ReaderBuilder, StringRecord};
 
-use super::models::{DataGroup, FieldGroup, GroupsConfig};
+use super::derived::DerivedEngine;
+use super::models::{DataGroup, DataRow, FieldGroup, GroupsConfig};
 use encoding_rs;
-use encoding_rs_io::DecodeReaderBytesBuilder;
 use regex::Regex;
 
 #[derive(Default, Debug)]
@@ -20,10 +20,94 @@ struct RecordAlignmentState {
     prev_record: Option<Vec<String>>,
 }
 
+/// Parser state carried over between reads of the same log.
+#[derive(Debug)]
+struct ParseContext {
+    headers: Vec<String>,
+    field_mappings: HashMap<String, Vec<String>>,
+    align_state: RecordAlignmentState,
+    last_date: Option<NaiveDate>,
+    /// HWiNFO only writes the tail meta lines once logging has stopped.
+    has_tail_meta: bool,
+}
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Text encoding of a log file.
+///
+/// HWiNFO writes UTF-8 with a BOM; older versions and some exports use the ANSI code page
+/// (GBK on Chinese Windows) for the whole file. Many GBK byte pairs are also valid UTF-8, so
+/// the choice is made once per file, from its header line, rather than per byte run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogEncoding {
+    Utf8,
+    Gbk,
+}
+
+impl LogEncoding {
+    /// UTF-8 when the bytes start with a BOM or the header line is valid UTF-8, GBK otherwise.
+    ///
+    /// Only the header decides: a UTF-8 log may still hold GBK `是`/`否` values further down,
+    /// which [`DataProcessor::decode_with`] reads run by run.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            return LogEncoding::Utf8;
+        }
+        let mut lines = bytes
+            .split(|&b| b == b'\n')
+            .skip_while(|line| line.trim_ascii().is_empty())
+            .peekable();
+        let header = lines.next().unwrap_or_default();
+        // A log still being written may end in the middle of a character.
+        let valid_utf8 = match std::str::from_utf8(header) {
+            Ok(_) => true,
+            Err(e) => e.error_len().is_none() && lines.peek().is_none(),
+        };
+        if valid_utf8 {
+            LogEncoding::Utf8
+        } else {
+            LogEncoding::Gbk
+        }
+    }
+}
+
+/// Follows a log that HWiNFO is still appending to.
+#[derive(Debug)]
+pub struct CsvFollower {
+    path: String,
+    /// Encoding detected when the log was first read; appended rows use the same one.
+    encoding: LogEncoding,
+    /// Byte offset just past the last complete line that has been parsed.
+    offset: u64,
+    ctx: ParseContext,
+}
+
+impl CsvFollower {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Column names of the log in CSV order.
+    pub fn headers(&self) -> &[String] {
+        &self.ctx.headers
+    }
+}
+
+/// Result of polling a followed log.
+#[derive(Debug)]
+pub enum FollowUpdate {
+    /// Rows appended since the last poll (empty if nothing complete was written).
+    Appended(Vec<DataRow>),
+    /// The whole file had to be re-processed; these rows replace everything seen so far.
+    Reloaded(Vec<DataRow>),
+}
+
 pub struct DataProcessor {
     config: GroupsConfig,
     static_field_map: HashMap<String, Vec<String>>,
     regex_rules: Vec<(Regex, Vec<String>)>,
+    /// `[[derived_sensors]]` from groups.toml, added to every parsed row.
+    derived: DerivedEngine,
 }
 
 #[derive(Debug, Clone)]
@@ -46,10 +130,16 @@ impl DataProcessor {
             Self::collect_field_mappings(group, &mut path, &mut static_field_map, &mut regex_rules);
         }
 
+        let derived = DerivedEngine::new(&config.derived_sensors).unwrap_or_else(|e| {
+            log::error!("派生传感器配置无效，已忽略: {e}");
+            DerivedEngine::default()
+        });
+
         DataProcessor {
             config,
             static_field_map,
             regex_rules,
+            derived,
         }
     }
 
@@ -84,26 +174,224 @@ impl DataProcessor {
         file_path: &str,
     ) -> Result<Vec<HashMap<String, DataGroup>>, Box<dyn Error>> {
         // 1) Read file as UTF-8 text lines (BOM handled by decoder), then strictly validate tail meta format.
-        let file = File::open(file_path)?;
-        let buf_reader = BufReader::new(file);
-        let transcoded_reader = DecodeReaderBytesBuilder::new()
-            .encoding(Some(encoding_rs::UTF_8))
-            .build(buf_reader);
-
-        let mut line_reader = BufReader::new(transcoded_reader);
-        let mut lines: Vec<String> = Vec::new();
-        let mut buf = String::new();
-        while line_reader.read_line(&mut buf)? != 0 {
-            if buf.ends_with('\n') {
-                buf.pop();
-                if buf.ends_with('\r') {
-                    buf.pop();
+        let lines = Self::read_lines(file_path)?;
+        let (all_records, _) = self.process_lines(&lines, file_path)?;
+        Ok(all_records)
+    }
+
+    /// Check that a file looks like an HWiNFO log without parsing its rows.
+    pub fn validate_csv_file(file_path: &str) -> Result<(), Box<dyn Error>> {
+        let lines = Self::read_lines(file_path)?;
+        Self::analyze_hwinfo_lines(&lines)?;
+        Ok(())
+    }
+
+    /// Cheap check for a log that may still be growing: only reads up to the first non-empty
+    /// line, which must be the subtitle header (`Date,Time,...`). The tail is not checked.
+    pub fn validate_csv_header(file_path: &str) -> Result<(), Box<dyn Error>> {
+        let mut reader = BufReader::new(File::open(file_path)?);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                return Err("CSV 文件为空".into());
+            }
+            let text = Self::decode_log_text(&line);
+            if Self::normalize_for_compare(&text).is_empty() {
+                continue;
+            }
+            if !Self::is_subtitle_header_line(&text) {
+                return Err("CSV 文件不符合预期：首个非空行不是子标题表头（Date,Time,...）".into());
+            }
+            return Ok(());
+        }
+    }
+
+    /// Read a file as text lines (BOM removed, line endings stripped).
+    fn read_lines(file_path: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let bytes = std::fs::read(file_path)?;
+        Ok(Self::decode_log_text(&bytes)
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    /// Decode log bytes, choosing the encoding once for the whole file (see [`LogEncoding`]).
+    pub fn decode_log_text(bytes: &[u8]) -> String {
+        Self::decode_with(bytes, LogEncoding::detect(bytes))
+    }
+
+    /// Decode log bytes in a known encoding.
+    pub fn decode_with(bytes: &[u8], encoding: LogEncoding) -> String {
+        match encoding {
+            LogEncoding::Gbk => encoding_rs::GBK.decode_without_bom_handling(bytes).0.into_owned(),
+            LogEncoding::Utf8 => Self::decode_utf8_with_legacy_values(bytes),
+        }
+    }
+
+    /// Decode UTF-8, reading the invalid runs as GBK.
+    ///
+    /// HWiNFO writes the log as UTF-8 but emits localized `Yes`/`No` values in the system ANSI
+    /// code page, e.g. `是`/`否` as GBK on Chinese Windows. A run lasts until the next byte
+    /// below 0x40, which can never be a GBK trail byte (commas, line breaks, digits), and is
+    /// decoded as a whole so the GBK decoder resynchronises after stray bytes.
+    fn decode_utf8_with_legacy_values(bytes: &[u8]) -> String {
+        let mut rest = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+        let mut out = String::with_capacity(rest.len());
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(text) => {
+                    out.push_str(text);
+                    return out;
+                }
+                Err(e) => {
+                    let (valid, invalid) = rest.split_at(e.valid_up_to());
+                    out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                    let end = invalid
+                        .iter()
+                        .position(|&b| b < 0x40)
+                        .unwrap_or(invalid.len())
+                        .max(1);
+                    let (text, _) = encoding_rs::GBK.decode_without_bom_handling(&invalid[..end]);
+                    out.push_str(&text);
+                    rest = &invalid[end..];
                 }
             }
-            lines.push(std::mem::take(&mut buf));
         }
+    }
 
-        let (header_line, tail_meta) = Self::analyze_hwinfo_lines(&lines)?;
+    /// Process a log that HWiNFO may still be writing, and return a follower that can later
+    /// pick up appended rows via [`DataProcessor::poll_appended`].
+    ///
+    /// Only complete (newline-terminated) lines are consumed; a partially written last line is
+    /// left for the next poll.
+    pub fn follow_csv_file(
+        &self,
+        file_path: &str,
+    ) -> Result<(Vec<DataRow>, CsvFollower), Box<dyn Error>> {
+        let bytes = std::fs::read(file_path)?;
+        let consumed = bytes
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+
+        let encoding = LogEncoding::detect(&bytes);
+        let text = Self::decode_with(&bytes[..consumed], encoding);
+        let lines: Vec<String> = text
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+
+        let (all_records, ctx) = self.process_lines(&lines, file_path)?;
+        let follower = CsvFollower {
+            path: file_path.to_string(),
+            encoding,
+            offset: consumed as u64,
+            ctx,
+        };
+        Ok((all_records, follower))
+    }
+
+    /// Parse rows appended to a followed log since the last poll.
+    ///
+    /// Falls back to a full re-process (returning [`FollowUpdate::Reloaded`]) when the file shrank,
+    /// or when HWiNFO has written the tail meta lines (repeated header + parent-title row), since
+    /// the parent titles may regroup every column.
+    pub fn poll_appended(&self, follower: &mut CsvFollower) -> Result<FollowUpdate, Box<dyn Error>> {
+        let len = std::fs::metadata(&follower.path)?.len();
+        if len == follower.offset {
+            return Ok(FollowUpdate::Appended(Vec::new()));
+        }
+        if len < follower.offset || follower.ctx.has_tail_meta {
+            log::info!("CSV 文件被截断或在结束后被改写，重新完整解析: {:?}", follower.path);
+            return self.reload_follower(follower);
+        }
+
+        let mut file = File::open(&follower.path)?;
+        file.seek(SeekFrom::Start(follower.offset))?;
+        let mut chunk = Vec::new();
+        file.read_to_end(&mut chunk)?;
+
+        // Split the appended bytes into complete lines, remembering where each one starts.
+        let mut lines: Vec<(usize, String)> = Vec::new();
+        let mut line_start = 0usize;
+        for (i, &b) in chunk.iter().enumerate() {
+            if b == b'\n' {
+                let text = Self::decode_with(&chunk[line_start..i], follower.encoding);
+                let text = text.strip_suffix('\r').unwrap_or(&text).to_string();
+                lines.push((line_start, text));
+                line_start = i + 1;
+            }
+        }
+        let mut consumed = line_start;
+
+        let mut data_lines: Vec<&str> = Vec::new();
+        for (pos, (start, line)) in lines.iter().enumerate() {
+            let norm = Self::normalize_for_compare(line);
+            if Self::is_subtitle_header_line(line) || norm.starts_with(",,") {
+                // HWiNFO stopped logging and is writing its tail meta lines. Wait until the
+                // parent-title row after the repeated header is complete, then re-process.
+                let tail_complete = norm.starts_with(",,")
+                    || lines[pos + 1..]
+                        .iter()
+                        .any(|(_, l)| !Self::normalize_for_compare(l).is_empty());
+                if tail_complete {
+                    log::info!("检测到 CSV 尾部分组元数据，重新完整解析: {:?}", follower.path);
+                    return self.reload_follower(follower);
+                }
+                consumed = *start;
+                break;
+            }
+            if !norm.is_empty() {
+                data_lines.push(line);
+            }
+        }
+
+        let mut csv_content = String::new();
+        for line in data_lines {
+            csv_content.push_str(line);
+            csv_content.push('\n');
+        }
+
+        let mut rdr = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(Cursor::new(csv_content));
+
+        let ctx = &mut follower.ctx;
+        let mut appended = Vec::new();
+        for result in rdr.records() {
+            let record = result?;
+            let processed_record =
+                Self::normalize_and_process_record(&record, ctx.headers.len(), &mut ctx.align_state)?;
+            appended.push(self.build_row(
+                &ctx.headers,
+                &ctx.field_mappings,
+                &processed_record,
+                &mut ctx.last_date,
+            ));
+        }
+
+        self.derived.apply(&mut appended);
+        follower.offset += consumed as u64;
+        Ok(FollowUpdate::Appended(appended))
+    }
+
+    fn reload_follower(&self, follower: &mut CsvFollower) -> Result<FollowUpdate, Box<dyn Error>> {
+        let (all_records, fresh) = self.follow_csv_file(&follower.path)?;
+        *follower = fresh;
+        Ok(FollowUpdate::Reloaded(all_records))
+    }
+
+    /// Validate and parse already-split log lines, returning the rows plus the parser context
+    /// needed to continue parsing rows appended later.
+    fn process_lines(
+        &self,
+        lines: &[String],
+        file_path: &str,
+    ) -> Result<(Vec<DataRow>, ParseContext), Box<dyn Error>> {
+        let tail_meta = Self::analyze_hwinfo_lines(lines)?;
 
         // 2) Reconstruct the CSV content excluding tail meta lines (if present) and parse data strictly.
         let mut csv_content = String::new();
@@ -154,35 +442,49 @@ impl DataProcessor {
         for result in rdr.records() {
             let record = result?;
             let processed_record = Self::normalize_and_process_record(&record, headers.len(), &mut align_state)?;
+            all_records.push(self.build_row(&headers, &field_mappings, &processed_record, &mut last_date));
+        }
+        self.derived.apply(&mut all_records);
 
-            // Prefer parsing timestamp from the original Date/Time columns (always the first two columns in HWiNFO CSV).
-            let date_str = processed_record.get(0).map(|s| s.as_str()).unwrap_or("");
-            let time_str = processed_record.get(1).map(|s| s.as_str()).unwrap_or("");
-            let ts_ms = Self::parse_hwinfo_datetime_to_timestamp_ms(date_str, time_str, &mut last_date);
+        let ctx = ParseContext {
+            headers,
+            field_mappings,
+            align_state,
+            last_date,
+            has_tail_meta: tail_meta.is_some(),
+        };
+        Ok((all_records, ctx))
+    }
 
-            let mut data_groups = HashMap::new();
-            for (header, value) in headers.iter().zip(processed_record.iter()) {
-                if let Some(path) = field_mappings.get(header) {
-                    self.insert_field(&mut data_groups, path, header, value.to_string());
-                }
+    /// Turn one normalized record into the grouped row structure.
+    fn build_row(
+        &self,
+        headers: &[String],
+        field_mappings: &HashMap<String, Vec<String>>,
+        processed_record: &[String],
+        last_date: &mut Option<NaiveDate>,
+    ) -> DataRow {
+        // Prefer parsing timestamp from the original Date/Time columns (always the first two columns in HWiNFO CSV).
+        let date_str = processed_record.first().map(|s| s.as_str()).unwrap_or("");
+        let time_str = processed_record.get(1).map(|s| s.as_str()).unwrap_or("");
+        let ts_ms = Self::parse_hwinfo_datetime_to_timestamp_ms(date_str, time_str, last_date);
+
+        let mut data_groups = HashMap::new();
+        for (header, value) in headers.iter().zip(processed_record.iter()) {
+            if let Some(path) = field_mappings.get(header) {
+                self.insert_field(&mut data_groups, path, header, value.to_string());
             }
+        }
 
-            // Inject computed timestamp into base group so the frontend can use it directly.
-            if let Some(ts_ms) = ts_ms {
-                if let Some(base) = data_groups.get_mut("base") {
-                    base.fields
-                        .insert("Timestamp".to_string(), ts_ms.to_string());
-                }
+        // Inject computed timestamp into base group so the frontend can use it directly.
+        if let Some(ts_ms) = ts_ms {
+            if let Some(base) = data_groups.get_mut("base") {
+                base.fields
+                    .insert("Timestamp".to_string(), ts_ms.to_string());
             }
-
-            all_records.push(data_groups);
         }
 
-        // Sanity: ensure the detected header line really was the first subtitle header.
-        // This is just to silence unused variable warnings in case future refactors remove it.
-        let _ = header_line;
-
-        Ok(all_records)
+        data_groups
     }
 
     /// Normalize headers by trimming trailing empty columns (commonly produced by a trailing comma).
@@ -200,7 +502,7 @@ impl DataProcessor {
     /// 1) subtitle header appears only once at the top
     /// 2) subtitle header appears at the top + repeated at the penultimate non-empty line,
     ///    and the last non-empty line is the parent-title row (Date/Time empty).
-    fn analyze_hwinfo_lines(lines: &[String]) -> Result<(String, Option<TailGroupMeta>), Box<dyn Error>> {
+    fn analyze_hwinfo_lines(lines: &[String]) -> Result<Option<TailGroupMeta>, Box<dyn Error>> {
         let non_empty_indices: Vec<usize> = lines
             .iter()
             .enumerate()
@@ -215,7 +517,6 @@ impl DataProcessor {
         }
 
         let header_idx = non_empty_indices[0];
-        let header_line = lines[header_idx].clone();
 
         // Count occurrences of any line that looks like the subtitle header (starts with Date,Time).
         let header_like_indices: Vec<usize> = non_empty_indices
@@ -240,7 +541,7 @@ impl DataProcessor {
                             .into(),
                     );
                 }
-                Ok((header_line, None))
+                Ok(None)
             }
             2 => {
                 if non_empty_indices.len() < 2 {
@@ -261,14 +562,11 @@ impl DataProcessor {
                     );
                 }
 
-                Ok((
-                    header_line,
-                    Some(TailGroupMeta {
-                        repeated_header_idx: second_header_idx,
-                        parent_line_idx: last_idx,
-                        parent_line: lines[last_idx].clone(),
-                    }),
-                ))
+                Ok(Some(TailGroupMeta {
+                    repeated_header_idx: second_header_idx,
+                    parent_line_idx: last_idx,
+                    parent_line: lines[last_idx].clone(),
+                }))
             }
             _ => Err(
                 "检测到多次子标题表头（Date,Time,...）出现次数超过 2；仅支持：表头只在头部出现，或头部+尾部两行（重复表头+父标题行）"
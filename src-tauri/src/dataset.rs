@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, HashMap};
+use std::mem::size_of;
+
+use serde::Serialize;
+
+use crate::merge::{DataGap, MergedLog};
+use crate::models::{DataGroup, DataRow, DerivedSensor};
+use crate::thresholds::ThresholdDetector;
+
+pub type DatasetId = u64;
+
+/// One loaded log (or other row source) kept in memory.
+#[derive(Debug)]
+pub struct Dataset {
+    pub id: DatasetId,
+    pub path: String,
+    /// Column names in CSV order; empty when the rows do not come from a single CSV.
+    pub columns: Vec<String>,
+    pub rows: Vec<DataRow>,
+    /// Derived sensors added at runtime; re-applied to appended or reloaded rows.
+    pub derived: Vec<DerivedSensor>,
+    /// Live threshold monitors fed with appended rows.
+    pub monitors: Vec<ThresholdDetector>,
+    /// Pauses between the logs of a merged dataset.
+    pub gaps: Vec<DataGap>,
+    /// Unix timestamp (ms) of when the dataset was loaded.
+    pub loaded_at: i64,
+}
+
+/// Lightweight description of a dataset for the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetInfo {
+    pub id: DatasetId,
+    pub path: String,
+    pub row_count: usize,
+    pub loaded_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetMemoryUsage {
+    pub id: DatasetId,
+    pub path: String,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryUsage {
+    pub total_bytes: usize,
+    pub datasets: Vec<DatasetMemoryUsage>,
+}
+
+impl Dataset {
+    pub fn info(&self) -> DatasetInfo {
+        DatasetInfo {
+            id: self.id,
+            path: self.path.clone(),
+            row_count: self.rows.len(),
+            loaded_at: self.loaded_at,
+        }
+    }
+
+    /// Whether `name` is already taken by a CSV column, a runtime derived sensor or a field of
+    /// any row (not just the first, since a sensor may only be written later).
+    pub fn has_sensor(&self, name: &str) -> bool {
+        self.columns.iter().any(|c| c == name)
+            || self.derived.iter().any(|s| s.name == name)
+            || self.rows.iter().any(|row| groups_contain(row, name))
+    }
+
+    /// Approximate heap usage of the rows, including hash map buckets and string buffers.
+    pub fn memory_usage(&self) -> usize {
+        let rows = self.rows.capacity() * size_of::<DataRow>();
+        rows + self.rows.iter().map(groups_memory_usage).sum::<usize>()
+    }
+}
+
+fn groups_contain(groups: &HashMap<String, DataGroup>, name: &str) -> bool {
+    groups
+        .values()
+        .any(|group| group.fields.contains_key(name) || groups_contain(&group.children, name))
+}
+
+fn groups_memory_usage(groups: &HashMap<String, DataGroup>) -> usize {
+    let buckets = groups.capacity() * (size_of::<(String, DataGroup)>() + 1);
+    buckets
+        + groups
+            .iter()
+            .map(|(name, group)| name.capacity() + group_memory_usage(group))
+            .sum::<usize>()
+}
+
+fn group_memory_usage(group: &DataGroup) -> usize {
+    let fields = group.fields.capacity() * (size_of::<(String, String)>() + 1)
+        + group
+            .fields
+            .iter()
+            .map(|(k, v)| k.capacity() + v.capacity())
+            .sum::<usize>();
+    fields + groups_memory_usage(&group.children)
+}
+
+/// Timestamp (unix ms) injected into the `base` group by the CSV processor.
+pub fn row_timestamp(row: &DataRow) -> Option<i64> {
+    row.get("base")?.fields.get("Timestamp")?.parse().ok()
+}
+
+/// All datasets that are currently loaded, keyed by an ID that is never reused.
+#[derive(Debug, Default)]
+pub struct DatasetRegistry {
+    next_id: DatasetId,
+    datasets: BTreeMap<DatasetId, Dataset>,
+}
+
+impl DatasetRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new dataset and return its ID.
+    pub fn insert(&mut self, path: String, rows: Vec<DataRow>) -> DatasetId {
+        self.insert_with_columns(path, Vec::new(), rows)
+    }
+
+    /// Register a dataset parsed from a CSV whose column order is known.
+    pub fn insert_with_columns(&mut self, path: String, columns: Vec<String>, rows: Vec<DataRow>) -> DatasetId {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.datasets.insert(
+            id,
+            Dataset {
+                id,
+                path,
+                columns,
+                rows,
+                derived: Vec::new(),
+                monitors: Vec::new(),
+                gaps: Vec::new(),
+                loaded_at: chrono::Local::now().timestamp_millis(),
+            },
+        );
+        id
+    }
+
+    /// Register several logs merged into one dataset.
+    pub fn insert_merged(&mut self, path: String, merged: MergedLog) -> DatasetId {
+        let id = self.insert_with_columns(path, merged.columns, merged.rows);
+        if let Some(dataset) = self.datasets.get_mut(&id) {
+            dataset.gaps = merged.gaps;
+        }
+        id
+    }
+
+    pub fn get(&self, id: DatasetId) -> Option<&Dataset> {
+        self.datasets.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: DatasetId) -> Option<&mut Dataset> {
+        self.datasets.get_mut(&id)
+    }
+
+    pub fn contains(&self, id: DatasetId) -> bool {
+        self.datasets.contains_key(&id)
+    }
+
+    pub fn remove(&mut self, id: DatasetId) -> Option<Dataset> {
+        self.datasets.remove(&id)
+    }
+
+    pub fn list(&self) -> Vec<DatasetInfo> {
+        self.datasets.values().map(Dataset::info).collect()
+    }
+
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let datasets: Vec<DatasetMemoryUsage> = self
+            .datasets
+            .values()
+            .map(|d| DatasetMemoryUsage {
+                id: d.id,
+                path: d.path.clone(),
+                bytes: d.memory_usage(),
+            })
+            .collect();
+        MemoryUsage {
+            total_bytes: datasets.iter().map(|d| d.bytes).sum(),
+            datasets,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::expr::Expr;
+use crate::models::{DataGroup, DataRow, DerivedSensor};
+use crate::series::{parse_value, FieldLocator};
+
+/// Check that a derived sensor definition can be evaluated.
+pub fn validate(sensor: &DerivedSensor) -> Result<Expr, String> {
+    if sensor.name.trim().is_empty() {
+        return Err("派生传感器名称不能为空".to_string());
+    }
+    if sensor.group.is_empty() || sensor.group.iter().any(|g| g.trim().is_empty()) {
+        return Err(format!("派生传感器「{}」的分组无效", sensor.name));
+    }
+    if sensor.group[0] == "base" {
+        return Err(format!("派生传感器「{}」不能放在 base 分组", sensor.name));
+    }
+    let expr = Expr::parse(&sensor.expr)
+        .map_err(|e| format!("派生传感器「{}」表达式错误: {e}", sensor.name))?;
+    if expr.sensors().contains(&sensor.name) {
+        return Err(format!("派生传感器「{}」不能引用自身", sensor.name));
+    }
+    Ok(expr)
+}
+
+/// Render a computed value in the shortest form that parses back to the same `f64`, so small
+/// deltas and ratios keep their full precision.
+pub fn format_value(value: f64) -> String {
+    // `-0.0` would otherwise print as "-0".
+    if value == 0.0 {
+        "0".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+struct Compiled {
+    sensor: DerivedSensor,
+    expr: Expr,
+    inputs: Vec<String>,
+}
+
+/// Evaluates derived sensors into rows, in definition order.
+#[derive(Default)]
+pub struct DerivedEngine {
+    compiled: Vec<Compiled>,
+}
+
+impl DerivedEngine {
+    pub fn new(sensors: &[DerivedSensor]) -> Result<Self, String> {
+        let compiled = sensors
+            .iter()
+            .map(|sensor| {
+                let expr = validate(sensor)?;
+                Ok(Compiled {
+                    sensor: sensor.clone(),
+                    inputs: expr.sensors(),
+                    expr,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { compiled })
+    }
+
+    /// Add every derived sensor to every row. Rows where an input is missing get an empty
+    /// value, so the sensor still exists (as a gap) like a regular HWiNFO column.
+    pub fn apply(&self, rows: &mut [DataRow]) {
+        let mut locators: Vec<Vec<FieldLocator>> = self
+            .compiled
+            .iter()
+            .map(|c| c.inputs.iter().map(|name| FieldLocator::new(name)).collect())
+            .collect();
+
+        for row in rows.iter_mut() {
+            for (compiled, locators) in self.compiled.iter().zip(locators.iter_mut()) {
+                let values: Vec<Option<f64>> = locators
+                    .iter_mut()
+                    .map(|l| l.get(row).and_then(parse_value))
+                    .collect();
+                let lookup = |name: &str| {
+                    let i = compiled.inputs.iter().position(|n| n == name)?;
+                    values[i]
+                };
+                let value = compiled.expr.eval(&lookup).map(format_value).unwrap_or_default();
+
+                let (top, rest) = compiled
+                    .sensor
+                    .group
+                    .split_first()
+                    .expect("validated group path");
+                row.entry(top.clone())
+                    .or_insert_with(DataGroup::new)
+                    .insert(rest, &compiled.sensor.name, value);
+            }
+        }
+    }
+}
+
+/// Remove a derived sensor from rows, dropping groups that become empty.
+pub fn remove_from_rows(rows: &mut [DataRow], sensor: &DerivedSensor) {
+    fn remove(group: &mut DataGroup, path: &[String], name: &str) {
+        match path.split_first() {
+            None => {
+                group.fields.remove(name);
+            }
+            Some((child, rest)) => {
+                if let Some(sub) = group.children.get_mut(child) {
+                    remove(sub, rest, name);
+                    if sub.fields.is_empty() && sub.children.is_empty() {
+                        group.children.remove(child);
+                    }
+                }
+            }
+        }
+    }
+
+    let Some((top, rest)) = sensor.group.split_first() else {
+        return;
+    };
+    for row in rows.iter_mut() {
+        if let Some(group) = row.get_mut(top) {
+            remove(group, rest, &sensor.name);
+            if group.fields.is_empty() && group.children.is_empty() {
+                row.remove(top);
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde::Deserialize;
+
+/// Downsampling strategy for chart series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Downsample {
+    /// Largest-Triangle-Three-Buckets: keeps the visual shape of the line.
+    #[default]
+    Lttb,
+    /// Keep the minimum and maximum sample of every bucket so spikes are never lost.
+    MinMax,
+}
+
+/// Pick the indices of `values` to keep so that at most about `max_points` samples remain.
+///
+/// Missing samples are not drawn, but the first index of every run of `None` is kept so the
+/// chart still shows the gap. The returned indices are sorted.
+pub fn downsample_indices(
+    timestamps: &[i64],
+    values: &[Option<f64>],
+    max_points: usize,
+    method: Downsample,
+) -> Vec<usize> {
+    let mut present = Vec::with_capacity(values.len());
+    let mut gaps = Vec::new();
+    for (i, value) in values.iter().enumerate() {
+        match value {
+            Some(_) => present.push(i),
+            None if i == 0 || values[i - 1].is_some() => gaps.push(i),
+            None => {}
+        }
+    }
+
+    let kept = if present.len() <= max_points.max(3) {
+        present
+    } else {
+        let y = |i: usize| values[i].unwrap_or_default();
+        match method {
+            Downsample::Lttb => lttb(timestamps, &present, y, max_points),
+            Downsample::MinMax => min_max(&present, y, max_points),
+        }
+    };
+
+    merge_sorted(kept, gaps)
+}
+
+fn lttb(timestamps: &[i64], candidates: &[usize], y: impl Fn(usize) -> f64, threshold: usize) -> Vec<usize> {
+    let threshold = threshold.max(3);
+    let n = candidates.len();
+    let x = |i: usize| timestamps[i] as f64;
+
+    let mut out = Vec::with_capacity(threshold);
+    out.push(candidates[0]);
+
+    // First and last points are fixed; the rest are split into `threshold - 2` buckets.
+    let bucket = (n - 2) as f64 / (threshold - 2) as f64;
+    let mut a = candidates[0];
+    for b in 0..threshold - 2 {
+        let start = (b as f64 * bucket) as usize + 1;
+        let end = (((b + 1) as f64 * bucket) as usize + 1).min(n - 1);
+
+        // Average of the next bucket is the third triangle vertex.
+        let next_start = end;
+        let next_end = (((b + 2) as f64 * bucket) as usize + 1).min(n);
+        let next = &candidates[next_start..next_end.max(next_start + 1)];
+        let avg_x = next.iter().map(|&i| x(i)).sum::<f64>() / next.len() as f64;
+        let avg_y = next.iter().map(|&i| y(i)).sum::<f64>() / next.len() as f64;
+
+        let (ax, ay) = (x(a), y(a));
+        let mut best = candidates[start];
+        let mut best_area = f64::MIN;
+        for &i in &candidates[start..end.max(start + 1)] {
+            let area = ((ax - avg_x) * (y(i) - ay) - (ax - x(i)) * (avg_y - ay)).abs();
+            if area > best_area {
+                best_area = area;
+                best = i;
+            }
+        }
+        out.push(best);
+        a = best;
+    }
+
+    out.push(candidates[n - 1]);
+    out
+}
+
+fn min_max(candidates: &[usize], y: impl Fn(usize) -> f64, max_points: usize) -> Vec<usize> {
+    let n = candidates.len();
+    // Two samples per bucket, plus the fixed first and last points.
+    let buckets = (max_points.saturating_sub(2) / 2).max(1);
+    let size = (n - 2) as f64 / buckets as f64;
+
+    let mut out = Vec::with_capacity(buckets * 2 + 2);
+    out.push(candidates[0]);
+    for b in 0..buckets {
+        let start = (b as f64 * size) as usize + 1;
+        let end = (((b + 1) as f64 * size) as usize + 1).min(n - 1);
+        let slice = &candidates[start..end];
+        if slice.is_empty() {
+            continue;
+        }
+
+        let mut lo = slice[0];
+        let mut hi = slice[0];
+        for &i in slice {
+            if y(i) < y(lo) {
+                lo = i;
+            }
+            if y(i) > y(hi) {
+                hi = i;
+            }
+        }
+        out.push(lo.min(hi));
+        if lo != hi {
+            out.push(lo.max(hi));
+        }
+    }
+    out.push(candidates[n - 1]);
+    out
+}
+
+fn merge_sorted(mut a: Vec<usize>, b: Vec<usize>) -> Vec<usize> {
+    if b.is_empty() {
+        return a;
+    }
+    a.extend(b);
+    a.sort_unstable();
+    a.dedup();
+    a
+}
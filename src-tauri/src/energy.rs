@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::parse_unit;
+use crate::models::DataRow;
+use crate::phases::{segment_phases, PhaseKind, PhaseQuery};
+use crate::series::{extract_series, sample_step, sensor_fields, SeriesQuery};
+
+/// Power sensors integrated when the query names none. Only top-level readings are listed:
+/// summing a package and its cores would count the same energy twice.
+const DEFAULT_SENSORS: &[&str] = &[
+    "CPU Package Power [W]",
+    "CPU 封装功率 [W]",
+    "GPU Power [W]",
+    "GPU 功率 [W]",
+];
+
+/// Without an explicit limit, intervals longer than this many typical sample steps are
+/// treated as gaps and not integrated.
+const GAP_STEPS: i64 = 5;
+
+/// Energy integration request. `sensors` must be power readings (`[W]`, `[mW]` or `[kW]`).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyQuery {
+    #[serde(default)]
+    pub sensors: Vec<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Intervals between samples longer than this are skipped instead of bridged.
+    pub max_gap_ms: Option<i64>,
+    /// Also break the energy down by load phase.
+    pub phases: Option<PhaseQuery>,
+}
+
+/// Energy over a time range. `average_w` is the energy divided by the integrated time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Energy {
+    pub joules: f64,
+    pub watt_hours: f64,
+    pub average_w: Option<f64>,
+    /// Time actually integrated, i.e. without gaps and missing samples.
+    pub covered_ms: i64,
+}
+
+impl Energy {
+    fn from_joules(joules: f64, covered_ms: i64) -> Self {
+        Self {
+            joules,
+            watt_hours: joules / 3600.0,
+            average_w: (covered_ms > 0).then(|| joules / (covered_ms as f64 / 1000.0)),
+            covered_ms,
+        }
+    }
+
+    /// Energy of several sensors together; the average is the sum of their averages.
+    pub fn combine(parts: &[Energy]) -> Self {
+        let joules = parts.iter().map(|e| e.joules).sum();
+        Self {
+            joules,
+            watt_hours: joules / 3600.0,
+            average_w: parts.iter().filter_map(|e| e.average_w).reduce(|a, b| a + b),
+            covered_ms: parts.iter().map(|e| e.covered_ms).max().unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorEnergy {
+    pub sensor: String,
+    #[serde(flatten)]
+    pub energy: Energy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseEnergy {
+    pub kind: PhaseKind,
+    pub start: i64,
+    pub end: i64,
+    pub duration_ms: i64,
+    pub total: Energy,
+    /// One entry per [`EnergyReport::sensors`].
+    pub sensors: Vec<Energy>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyReport {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub sensors: Vec<SensorEnergy>,
+    pub total: Energy,
+    /// Load sensor used for the phase breakdown.
+    pub phase_sensor: Option<String>,
+    pub phases: Vec<PhaseEnergy>,
+}
+
+/// Factor converting the sensor's unit to watts.
+pub fn watts_per_unit(sensor: &str) -> Option<f64> {
+    match parse_unit(sensor)?.as_str() {
+        "W" => Some(1.0),
+        "mW" => Some(0.001),
+        "kW" => Some(1000.0),
+        _ => None,
+    }
+}
+
+/// Trapezoidal integral of `watts` over `[start, end]` (unix ms). Only intervals with both
+/// samples present and no longer than `max_gap_ms` contribute.
+pub fn integrate(
+    timestamps: &[i64],
+    watts: &[Option<f64>],
+    start: i64,
+    end: i64,
+    max_gap_ms: i64,
+) -> Energy {
+    let mut joules = 0.0;
+    let mut covered_ms = 0;
+    for (t, w) in timestamps.windows(2).zip(watts.windows(2)) {
+        if t[0] < start || t[1] > end {
+            continue;
+        }
+        let dt = t[1] - t[0];
+        if dt <= 0 || dt > max_gap_ms {
+            continue;
+        }
+        if let (Some(a), Some(b)) = (w[0], w[1]) {
+            joules += (a + b) / 2.0 * dt as f64 / 1000.0;
+            covered_ms += dt;
+        }
+    }
+    Energy::from_joules(joules, covered_ms)
+}
+
+fn default_sensors(rows: &[DataRow]) -> Vec<String> {
+    let Some(first) = rows.first() else {
+        return Vec::new();
+    };
+    let fields = sensor_fields(first);
+    DEFAULT_SENSORS
+        .iter()
+        .filter(|candidate| fields.iter().any(|(_, sensor)| sensor == *candidate))
+        .map(|s| s.to_string())
+        .collect()
+}
+
+pub fn integrate_energy(rows: &[DataRow], query: &EnergyQuery) -> Result<EnergyReport, String> {
+    let sensors = if query.sensors.is_empty() {
+        default_sensors(rows)
+    } else {
+        query.sensors.clone()
+    };
+    if sensors.is_empty() {
+        return Err("请选择至少一个功率传感器".to_string());
+    }
+    let mut scales = Vec::with_capacity(sensors.len());
+    for sensor in &sensors {
+        let scale = watts_per_unit(sensor).ok_or_else(|| format!("传感器 {sensor} 不是功率传感器"))?;
+        scales.push(scale);
+    }
+    if query.max_gap_ms.is_some_and(|gap| gap <= 0) {
+        return Err("最大间隔必须大于 0".to_string());
+    }
+
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: sensors.clone(),
+            start: query.start,
+            end: query.end,
+            ..Default::default()
+        },
+    );
+    let max_gap_ms = query
+        .max_gap_ms
+        .unwrap_or_else(|| sample_step(&set.timestamps).max(1) * GAP_STEPS);
+    let watts: Vec<Vec<Option<f64>>> = set
+        .series
+        .iter()
+        .zip(&scales)
+        .map(|(s, scale)| s.values.iter().map(|v| v.map(|v| v * scale)).collect())
+        .collect();
+    let over = |start: i64, end: i64| -> Vec<Energy> {
+        watts
+            .iter()
+            .map(|w| integrate(&set.timestamps, w, start, end, max_gap_ms))
+            .collect()
+    };
+
+    let whole = over(i64::MIN, i64::MAX);
+    let total = Energy::combine(&whole);
+
+    let (phase_sensor, phases) = match &query.phases {
+        Some(phase_query) => {
+            let phase_query = PhaseQuery {
+                start: phase_query.start.or(query.start),
+                end: phase_query.end.or(query.end),
+                ..phase_query.clone()
+            };
+            let report = segment_phases(rows, &phase_query)?;
+            let phases = report
+                .phases
+                .iter()
+                .map(|phase| {
+                    let energies = over(phase.start, phase.end);
+                    PhaseEnergy {
+                        kind: phase.kind,
+                        start: phase.start,
+                        end: phase.end,
+                        duration_ms: phase.duration_ms,
+                        total: Energy::combine(&energies),
+                        sensors: energies,
+                    }
+                })
+                .collect();
+            (Some(report.sensor), phases)
+        }
+        None => (None, Vec::new()),
+    };
+
+    Ok(EnergyReport {
+        start: set.timestamps.first().copied(),
+        end: set.timestamps.last().copied(),
+        sensors: sensors
+            .into_iter()
+            .zip(whole)
+            .map(|(sensor, energy)| SensorEnergy { sensor, energy })
+            .collect(),
+        total,
+        phase_sensor,
+        phases,
+    })
+}
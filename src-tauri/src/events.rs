@@ -0,0 +1,73 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::dataset::DatasetId;
+use crate::models::DataRow;
+use crate::thresholds::Episode;
+
+/// A CSV was (re)loaded and the dataset now holds all of its rows.
+pub const DATASET_LOADED: &str = "dataset-loaded";
+/// A dataset was closed and its rows dropped.
+pub const DATASET_CLOSED: &str = "dataset-closed";
+/// HWiNFO appended rows to the followed CSV; only the new rows are sent.
+pub const ROWS_APPENDED: &str = "rows-appended";
+/// `groups.toml` changed and was reloaded.
+pub const CONFIG_RELOADED: &str = "config-reloaded";
+/// Loading or following a CSV failed.
+pub const PARSE_ERROR: &str = "parse-error";
+/// A live threshold monitor saw an episode end.
+pub const THRESHOLD_EPISODE: &str = "threshold-episode";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetLoaded {
+    pub dataset_id: DatasetId,
+    pub path: String,
+    pub row_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetClosed {
+    pub dataset_id: DatasetId,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowsAppended {
+    pub dataset_id: DatasetId,
+    pub path: String,
+    /// Index of the first appended row in the dataset.
+    pub start_index: usize,
+    pub rows: Vec<DataRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloaded {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseError {
+    /// `None` when the file could not be loaded as a dataset at all.
+    pub dataset_id: Option<DatasetId>,
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdEpisode {
+    pub dataset_id: DatasetId,
+    pub episode: Episode,
+}
+
+/// Emit an event to every window; failures are only logged since nothing can be done about them.
+pub fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        log::error!("发送事件失败（{event}）: {e}");
+    }
+}
@@ -0,0 +1,441 @@
+use std::fmt;
+
+/// Arithmetic expression over sensor values.
+///
+/// Sensors are referenced by their full name in backticks, e.g.
+/// `` `CPU Package Power [W]` + `GPU Power [W]` ``. Supported: numbers, `+ - * / % ^`,
+/// comparisons (`< <= > >= == !=`, yielding 1 or 0), parentheses and the functions
+/// `abs sqrt round floor ceil min max avg sum pow clamp if`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Sensor(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Abs,
+    Sqrt,
+    Round,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+    Avg,
+    Sum,
+    Pow,
+    Clamp,
+    If,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "abs" => Func::Abs,
+            "sqrt" => Func::Sqrt,
+            "round" => Func::Round,
+            "floor" => Func::Floor,
+            "ceil" => Func::Ceil,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            "avg" => Func::Avg,
+            "sum" => Func::Sum,
+            "pow" => Func::Pow,
+            "clamp" => Func::Clamp,
+            "if" => Func::If,
+            _ => return None,
+        })
+    }
+
+    /// Allowed argument count as (min, max).
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Func::Abs | Func::Sqrt | Func::Round | Func::Floor | Func::Ceil => (1, 1),
+            Func::Min | Func::Max | Func::Avg | Func::Sum => (1, usize::MAX),
+            Func::Pow => (2, 2),
+            Func::Clamp | Func::If => (3, 3),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {})", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Sensor(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(src: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    const OPS: [&str; 12] = ["<=", ">=", "==", "!=", "+", "-", "*", "/", "%", "^", "<", ">"];
+
+    let err = |position: usize, message: &str| ParseError {
+        position,
+        message: message.to_string(),
+    };
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some(&(pos, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '`' {
+            chars.next();
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '`')) => break,
+                    Some((_, c)) => name.push(c),
+                    None => return Err(err(pos, "unterminated sensor name")),
+                }
+            }
+            if name.trim().is_empty() {
+                return Err(err(pos, "empty sensor name"));
+            }
+            tokens.push((pos, Token::Sensor(name)));
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = pos;
+            while let Some(&(i, c)) = chars.peek() {
+                let exponent_sign = (c == '-' || c == '+') && src[..i].ends_with(['e', 'E']);
+                if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let num = src[pos..end]
+                .parse::<f64>()
+                .map_err(|_| err(pos, "invalid number"))?;
+            tokens.push((pos, Token::Num(num)));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    ident.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push((pos, Token::Ident(ident)));
+        } else if c == '(' || c == ')' || c == ',' {
+            chars.next();
+            tokens.push((
+                pos,
+                match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                },
+            ));
+        } else if let Some(op) = OPS.iter().find(|op| src[pos..].starts_with(**op)) {
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push((pos, Token::Op(op)));
+        } else {
+            return Err(err(pos, &format!("unexpected character '{c}'")));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Deepest nesting of parentheses, function calls and unary operators the parser accepts;
+/// expressions come from the UI, so unbounded recursion could overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    len: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map(|(p, _)| *p).unwrap_or(self.len)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            position: self.position(),
+            message: message.into(),
+        }
+    }
+
+    fn eat_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), ParseError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("expected {what}")))
+        }
+    }
+
+    // comparison := additive (cmp additive)?
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let lhs = self.additive()?;
+        let Some(op) = self.eat_op(&["<", "<=", ">", ">=", "==", "!="]) else {
+            return Ok(lhs);
+        };
+        let op = match op {
+            "<" => BinOp::Lt,
+            "<=" => BinOp::Le,
+            ">" => BinOp::Gt,
+            ">=" => BinOp::Ge,
+            "==" => BinOp::Eq,
+            _ => BinOp::Ne,
+        };
+        let rhs = self.additive()?;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.term()?;
+        while let Some(op) = self.eat_op(&["+", "-"]) {
+            let op = if op == "+" { BinOp::Add } else { BinOp::Sub };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.unary()?;
+        while let Some(op) = self.eat_op(&["*", "/", "%"]) {
+            let op = match op {
+                "*" => BinOp::Mul,
+                "/" => BinOp::Div,
+                _ => BinOp::Rem,
+            };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    // Every nested level (sign, parentheses, function argument, exponent) passes through here.
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("expression is nested too deeply"));
+        }
+        self.depth += 1;
+        let expr = self.signed();
+        self.depth -= 1;
+        expr
+    }
+
+    fn signed(&mut self) -> Result<Expr, ParseError> {
+        if self.eat_op(&["-"]).is_some() {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat_op(&["+"]).is_some() {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    // power := primary ('^' unary)?, right associative
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.primary()?;
+        if self.eat_op(&["^"]).is_some() {
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(BinOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let Some((_, token)) = self.tokens.get(self.pos).cloned() else {
+            return Err(self.error("unexpected end of expression"));
+        };
+        match token {
+            Token::Num(n) => {
+                self.pos += 1;
+                Ok(Expr::Num(n))
+            }
+            Token::Sensor(name) => {
+                self.pos += 1;
+                Ok(Expr::Sensor(name))
+            }
+            Token::LParen => {
+                self.pos += 1;
+                let inner = self.comparison()?;
+                self.expect(Token::RParen, "')'")?;
+                Ok(inner)
+            }
+            Token::Ident(name) => {
+                let Some(func) = Func::from_name(&name) else {
+                    return Err(self.error(format!(
+                        "unknown function '{name}' (sensor names must be quoted with backticks)"
+                    )));
+                };
+                self.pos += 1;
+                self.expect(Token::LParen, "'(' after function name")?;
+                let mut args = Vec::new();
+                if self.peek() != Some(&Token::RParen) {
+                    loop {
+                        args.push(self.comparison()?);
+                        if self.peek() == Some(&Token::Comma) {
+                            self.pos += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::RParen, "')'")?;
+                let (min, max) = func.arity();
+                if args.len() < min || args.len() > max {
+                    return Err(self.error(format!("wrong number of arguments for '{name}'")));
+                }
+                Ok(Expr::Call(func, args))
+            }
+            _ => Err(self.error("unexpected token")),
+        }
+    }
+}
+
+impl Expr {
+    pub fn parse(src: &str) -> Result<Expr, ParseError> {
+        let tokens = tokenize(src)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            len: src.len(),
+            depth: 0,
+        };
+        let expr = parser.comparison()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(parser.error("unexpected token"));
+        }
+        Ok(expr)
+    }
+
+    /// Sensor names referenced by the expression, in order of first use.
+    pub fn sensors(&self) -> Vec<String> {
+        fn walk(expr: &Expr, out: &mut Vec<String>) {
+            match expr {
+                Expr::Num(_) => {}
+                Expr::Sensor(name) => {
+                    if !out.contains(name) {
+                        out.push(name.clone());
+                    }
+                }
+                Expr::Neg(inner) => walk(inner, out),
+                Expr::Binary(_, lhs, rhs) => {
+                    walk(lhs, out);
+                    walk(rhs, out);
+                }
+                Expr::Call(_, args) => args.iter().for_each(|a| walk(a, out)),
+            }
+        }
+        let mut out = Vec::new();
+        walk(self, &mut out);
+        out
+    }
+
+    /// Evaluate with sensor values from `lookup`. Missing sensors, division by zero and other
+    /// non-finite results yield `None`.
+    pub fn eval(&self, lookup: &impl Fn(&str) -> Option<f64>) -> Option<f64> {
+        let value = match self {
+            Expr::Num(n) => *n,
+            Expr::Sensor(name) => lookup(name)?,
+            Expr::Neg(inner) => -inner.eval(lookup)?,
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(lookup)?, rhs.eval(lookup)?);
+                let flag = |c: bool| if c { 1.0 } else { 0.0 };
+                match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    BinOp::Div if b == 0.0 => return None,
+                    BinOp::Div => a / b,
+                    BinOp::Rem if b == 0.0 => return None,
+                    BinOp::Rem => a % b,
+                    BinOp::Pow => a.powf(b),
+                    BinOp::Lt => flag(a < b),
+                    BinOp::Le => flag(a <= b),
+                    BinOp::Gt => flag(a > b),
+                    BinOp::Ge => flag(a >= b),
+                    BinOp::Eq => flag(a == b),
+                    BinOp::Ne => flag(a != b),
+                }
+            }
+            Expr::Call(Func::If, args) => {
+                // Only the chosen branch has to be available.
+                if args[0].eval(lookup)? != 0.0 {
+                    args[1].eval(lookup)?
+                } else {
+                    args[2].eval(lookup)?
+                }
+            }
+            Expr::Call(func, args) => {
+                let values = args.iter().map(|a| a.eval(lookup)).collect::<Option<Vec<f64>>>()?;
+                match func {
+                    Func::Abs => values[0].abs(),
+                    Func::Sqrt => values[0].sqrt(),
+                    Func::Round => values[0].round(),
+                    Func::Floor => values[0].floor(),
+                    Func::Ceil => values[0].ceil(),
+                    Func::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+                    Func::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    Func::Sum => values.iter().sum(),
+                    Func::Avg => values.iter().sum::<f64>() / values.len() as f64,
+                    Func::Pow => values[0].powf(values[1]),
+                    Func::Clamp => values[0].clamp(values[1].min(values[2]), values[1].max(values[2])),
+                    Func::If => unreachable!("handled above"),
+                }
+            }
+        };
+        value.is_finite().then_some(value)
+    }
+}
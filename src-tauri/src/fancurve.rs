@@ -0,0 +1,454 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataRow;
+use crate::series::{extract_series, SeriesQuery};
+use crate::smoothing::{smooth, Smoothing};
+use crate::stats::percentile;
+
+/// Fewest paired samples a curve is fitted on.
+const MIN_SAMPLES: usize = 10;
+
+const MAX_SEGMENTS: usize = 10;
+
+/// Window of the moving average used to tell rising from falling temperature.
+const TREND_WINDOW_MS: i64 = 20_000;
+
+/// Temperature slopes below this (°C per minute) count as steady.
+const STEADY_SLOPE_PER_MIN: f64 = 0.5;
+
+/// Default oscillation threshold as a share of the fan's P5-P95 range.
+const DEFAULT_AMPLITUDE_SHARE: f64 = 0.1;
+
+/// Hysteresis smaller than this share of the fan range is reported as none.
+const HYSTERESIS_SHARE: f64 = 0.03;
+
+fn default_segments() -> usize {
+    4
+}
+
+fn default_max_points() -> usize {
+    2_000
+}
+
+fn default_max_period_ms() -> i64 {
+    60_000
+}
+
+fn default_min_cycles() -> usize {
+    3
+}
+
+/// Fan curve request: a fan or pump speed/duty sensor against the temperature driving it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FanCurveQuery {
+    pub fan_sensor: String,
+    pub temperature_sensor: String,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Linear segments of the fitted curve.
+    #[serde(default = "default_segments")]
+    pub segments: usize,
+    /// Scatter points returned; the fit always uses every sample.
+    #[serde(default = "default_max_points")]
+    pub max_points: usize,
+    /// Smallest fan swing counted as oscillation, in the fan sensor's unit.
+    pub min_amplitude: Option<f64>,
+    /// Longest full cycle still counted as hunting.
+    #[serde(default = "default_max_period_ms")]
+    pub max_period_ms: i64,
+    #[serde(default = "default_min_cycles")]
+    pub min_cycles: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurvePoint {
+    pub timestamp: i64,
+    pub temperature: f64,
+    pub fan: f64,
+    pub trend: Trend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Breakpoint {
+    pub temperature: f64,
+    pub fan: f64,
+}
+
+/// Continuous piecewise-linear curve through `breakpoints` (ascending temperature).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PiecewiseFit {
+    pub breakpoints: Vec<Breakpoint>,
+    pub rmse: f64,
+}
+
+impl PiecewiseFit {
+    /// Fan value at `temperature`, extrapolating the outer segments.
+    pub fn at(&self, temperature: f64) -> f64 {
+        let bp = &self.breakpoints;
+        if bp.len() == 1 {
+            return bp[0].fan;
+        }
+        let i = bp[1..bp.len() - 1]
+            .iter()
+            .take_while(|b| b.temperature <= temperature)
+            .count();
+        let (a, b) = (bp[i], bp[i + 1]);
+        a.fan + (b.fan - a.fan) * (temperature - a.temperature) / (b.temperature - a.temperature)
+    }
+}
+
+/// Fan level while cooling down minus fan level while heating up at the same temperature.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hysteresis {
+    pub fan_gap: f64,
+    /// The gap expressed as a temperature shift along the fitted curve.
+    pub temperature_offset: Option<f64>,
+    pub detected: bool,
+}
+
+/// A run of fast fan swings, i.e. the fan hunting around a set point.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OscillationEpisode {
+    pub start: i64,
+    pub end: i64,
+    pub cycles: usize,
+    pub mean_period_ms: i64,
+    /// Mean peak-to-trough swing.
+    pub amplitude: f64,
+    /// Temperature range over the episode; small values point at a curve that is too steep.
+    pub temperature_span: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FanCurveReport {
+    pub fan_sensor: String,
+    pub temperature_sensor: String,
+    pub samples: usize,
+    pub points: Vec<CurvePoint>,
+    pub curve: PiecewiseFit,
+    pub rising_curve: Option<PiecewiseFit>,
+    pub falling_curve: Option<PiecewiseFit>,
+    pub hysteresis: Option<Hysteresis>,
+    pub oscillation_threshold: f64,
+    pub oscillations: Vec<OscillationEpisode>,
+}
+
+/// Solve `a x = b` by Gaussian elimination with partial pivoting.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-9 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (x, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Least-squares continuous piecewise-linear fit with `segments` pieces whose knots sit at
+/// temperature quantiles. Knots falling on the same temperature are merged.
+pub fn fit_piecewise(temperatures: &[f64], fans: &[f64], segments: usize) -> Option<PiecewiseFit> {
+    if temperatures.len() < MIN_SAMPLES {
+        return None;
+    }
+    let mut sorted = temperatures.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (low, high) = (sorted[0], sorted[sorted.len() - 1]);
+    let mut knots: Vec<f64> = (1..segments)
+        .filter_map(|j| percentile(&sorted, 100.0 * j as f64 / segments as f64))
+        .filter(|k| *k > low && *k < high)
+        .collect();
+    knots.dedup();
+
+    // Hinge basis: 1, t, max(t - k, 0) for every knot.
+    let basis = |t: f64| -> Vec<f64> {
+        let mut row = vec![1.0, t - low];
+        row.extend(knots.iter().map(|k| (t - k).max(0.0)));
+        row
+    };
+    let p = knots.len() + 2;
+    let mut ata = vec![vec![0.0; p]; p];
+    let mut aty = vec![0.0; p];
+    for (t, y) in temperatures.iter().zip(fans) {
+        let row = basis(*t);
+        for i in 0..p {
+            aty[i] += row[i] * y;
+            for j in 0..p {
+                ata[i][j] += row[i] * row[j];
+            }
+        }
+    }
+    let coef = solve(ata, aty)?;
+    let eval = |t: f64| basis(t).iter().zip(&coef).map(|(b, c)| b * c).sum::<f64>();
+
+    let sse: f64 = temperatures.iter().zip(fans).map(|(t, y)| (y - eval(*t)).powi(2)).sum();
+    let breakpoints = std::iter::once(low)
+        .chain(knots.iter().copied())
+        .chain(std::iter::once(high))
+        .map(|temperature| Breakpoint {
+            temperature,
+            fan: eval(temperature),
+        })
+        .collect();
+    Some(PiecewiseFit {
+        breakpoints,
+        rmse: (sse / temperatures.len() as f64).sqrt(),
+    })
+}
+
+/// Direction of the (smoothed) temperature at every sample.
+pub fn temperature_trend(timestamps: &[i64], temperatures: &[f64]) -> Vec<Trend> {
+    let values: Vec<Option<f64>> = temperatures.iter().map(|t| Some(*t)).collect();
+    let smoothed = smooth(
+        timestamps,
+        &values,
+        &Smoothing::Sma {
+            window_ms: TREND_WINDOW_MS,
+        },
+    );
+    let n = timestamps.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (i.saturating_sub(1), (i + 1).min(n - 1));
+            let dt_min = (timestamps[b] - timestamps[a]) as f64 / 60_000.0;
+            let slope = match (smoothed[a], smoothed[b]) {
+                (Some(x), Some(y)) if dt_min > 0.0 => (y - x) / dt_min,
+                _ => 0.0,
+            };
+            if slope >= STEADY_SLOPE_PER_MIN {
+                Trend::Rising
+            } else if slope <= -STEADY_SLOPE_PER_MIN {
+                Trend::Falling
+            } else {
+                Trend::Steady
+            }
+        })
+        .collect()
+}
+
+/// Indices of the alternating peaks and troughs that are at least `threshold` apart.
+pub fn swing_pivots(values: &[f64], threshold: f64) -> Vec<usize> {
+    let mut pivots = Vec::new();
+    let (mut lo, mut hi) = (0, 0);
+    // 0 until the first swing, then the direction of the swing in progress.
+    let mut direction = 0;
+    let mut extreme = 0;
+    for i in 1..values.len() {
+        let v = values[i];
+        match direction {
+            0 => {
+                if v > values[hi] {
+                    hi = i;
+                }
+                if v < values[lo] {
+                    lo = i;
+                }
+                if values[hi] - values[lo] >= threshold {
+                    let (first, last, dir) = if lo < hi { (lo, hi, 1) } else { (hi, lo, -1) };
+                    pivots.push(first);
+                    extreme = last;
+                    direction = dir;
+                }
+            }
+            1 if v > values[extreme] => extreme = i,
+            1 if values[extreme] - v >= threshold => {
+                pivots.push(extreme);
+                extreme = i;
+                direction = -1;
+            }
+            -1 if v < values[extreme] => extreme = i,
+            -1 if v - values[extreme] >= threshold => {
+                pivots.push(extreme);
+                extreme = i;
+                direction = 1;
+            }
+            _ => {}
+        }
+    }
+    if direction != 0 {
+        pivots.push(extreme);
+    }
+    pivots
+}
+
+/// Runs of swings whose half-periods stay within `max_period_ms / 2` and that last at least
+/// `min_cycles` full cycles.
+pub fn find_oscillations(
+    timestamps: &[i64],
+    fans: &[f64],
+    temperatures: &[f64],
+    threshold: f64,
+    max_period_ms: i64,
+    min_cycles: usize,
+) -> Vec<OscillationEpisode> {
+    let pivots = swing_pivots(fans, threshold);
+    let mut episodes = Vec::new();
+    let mut run_start = 0;
+    for k in 1..=pivots.len() {
+        let fast = k < pivots.len()
+            && (timestamps[pivots[k]] - timestamps[pivots[k - 1]]) * 2 <= max_period_ms;
+        if fast {
+            continue;
+        }
+        let run = &pivots[run_start..k];
+        run_start = k;
+        let swings = run.len().saturating_sub(1);
+        if swings < min_cycles.max(1) * 2 {
+            continue;
+        }
+        let (first, last) = (run[0], run[run.len() - 1]);
+        let amplitude =
+            run.windows(2).map(|w| (fans[w[1]] - fans[w[0]]).abs()).sum::<f64>() / swings as f64;
+        let span = &temperatures[first..=last];
+        let max = span.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let min = span.iter().copied().fold(f64::INFINITY, f64::min);
+        episodes.push(OscillationEpisode {
+            start: timestamps[first],
+            end: timestamps[last],
+            cycles: swings / 2,
+            mean_period_ms: (timestamps[last] - timestamps[first]) * 2 / swings as i64,
+            amplitude,
+            temperature_span: max - min,
+        });
+    }
+    episodes
+}
+
+pub fn fan_curve(rows: &[DataRow], query: &FanCurveQuery) -> Result<FanCurveReport, String> {
+    if !(1..=MAX_SEGMENTS).contains(&query.segments) {
+        return Err(format!("分段数必须在 1 到 {MAX_SEGMENTS} 之间"));
+    }
+    if query.min_amplitude.is_some_and(|a| a <= 0.0) {
+        return Err("振荡幅度阈值必须大于 0".to_string());
+    }
+
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: vec![query.fan_sensor.clone(), query.temperature_sensor.clone()],
+            start: query.start,
+            end: query.end,
+            ..Default::default()
+        },
+    );
+    let (mut timestamps, mut fans, mut temperatures) = (Vec::new(), Vec::new(), Vec::new());
+    for (i, ts) in set.timestamps.iter().enumerate() {
+        if let (Some(fan), Some(temperature)) = (set.series[0].values[i], set.series[1].values[i]) {
+            timestamps.push(*ts);
+            fans.push(fan);
+            temperatures.push(temperature);
+        }
+    }
+    if timestamps.len() < MIN_SAMPLES {
+        return Err("风扇与温度传感器的有效样本不足".to_string());
+    }
+
+    let curve = fit_piecewise(&temperatures, &fans, query.segments)
+        .ok_or_else(|| "温度没有变化，无法拟合风扇曲线".to_string())?;
+    let trends = temperature_trend(&timestamps, &temperatures);
+
+    let mut sorted_fans = fans.clone();
+    sorted_fans.sort_by(f64::total_cmp);
+    let fan_range =
+        percentile(&sorted_fans, 95.0).unwrap_or(0.0) - percentile(&sorted_fans, 5.0).unwrap_or(0.0);
+
+    let subset = |trend: Trend| -> (Vec<f64>, Vec<f64>) {
+        trends
+            .iter()
+            .zip(temperatures.iter().zip(&fans))
+            .filter(|(t, _)| **t == trend)
+            .map(|(_, (t, f))| (*t, *f))
+            .unzip()
+    };
+    let (rising_t, rising_f) = subset(Trend::Rising);
+    let (falling_t, falling_f) = subset(Trend::Falling);
+    let rising_curve = fit_piecewise(&rising_t, &rising_f, query.segments);
+    let falling_curve = fit_piecewise(&falling_t, &falling_f, query.segments);
+
+    // Mean residual against the overall curve, per direction: robust to the two
+    // directions covering different temperature ranges.
+    let mean_residual = |ts: &[f64], fs: &[f64]| {
+        ts.iter().zip(fs).map(|(t, f)| f - curve.at(*t)).sum::<f64>() / ts.len() as f64
+    };
+    let hysteresis = (rising_t.len() >= MIN_SAMPLES && falling_t.len() >= MIN_SAMPLES).then(|| {
+        let fan_gap = mean_residual(&falling_t, &falling_f) - mean_residual(&rising_t, &rising_f);
+        let bp = &curve.breakpoints;
+        let (first, last) = (bp[0], bp[bp.len() - 1]);
+        let slope = (last.fan - first.fan) / (last.temperature - first.temperature);
+        Hysteresis {
+            fan_gap,
+            temperature_offset: (slope.abs() > 1e-9).then(|| fan_gap / slope),
+            detected: fan_gap.abs() >= fan_range * HYSTERESIS_SHARE && fan_range > 0.0,
+        }
+    });
+
+    let oscillation_threshold = query
+        .min_amplitude
+        .unwrap_or(fan_range * DEFAULT_AMPLITUDE_SHARE);
+    let oscillations = if oscillation_threshold > 0.0 {
+        find_oscillations(
+            &timestamps,
+            &fans,
+            &temperatures,
+            oscillation_threshold,
+            query.max_period_ms,
+            query.min_cycles,
+        )
+    } else {
+        Vec::new()
+    };
+
+    let stride = timestamps.len().div_ceil(query.max_points.max(1));
+    let points = (0..timestamps.len())
+        .step_by(stride)
+        .map(|i| CurvePoint {
+            timestamp: timestamps[i],
+            temperature: temperatures[i],
+            fan: fans[i],
+            trend: trends[i],
+        })
+        .collect();
+
+    Ok(FanCurveReport {
+        fan_sensor: query.fan_sensor.clone(),
+        temperature_sensor: query.temperature_sensor.clone(),
+        samples: timestamps.len(),
+        points,
+        curve,
+        rising_curve,
+        falling_curve,
+        hysteresis,
+        oscillation_threshold,
+        oscillations,
+    })
+}
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataRow;
+use crate::series::{extract_series, sample_step, SeriesQuery};
+
+/// Upper bound for automatic binning.
+const MAX_AUTO_BINS: usize = 200;
+/// A pause longer than this many typical sample intervals (e.g. between merged logs) is
+/// counted as a single interval, so the value before it does not soak up the gap.
+const MAX_GAP_STEPS: i64 = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Weighting {
+    /// Each sample counts for the time until the next one (time residency).
+    #[default]
+    Time,
+    Count,
+}
+
+/// Histogram request. Explicit `edges` win over `bins`; with neither, the bin count is chosen
+/// automatically (Freedman-Diaconis, Sturges as fallback).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramQuery {
+    pub sensor: String,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub bins: Option<usize>,
+    /// Strictly increasing bin edges; values outside them go to `underflow`/`overflow`.
+    pub edges: Option<Vec<f64>>,
+    #[serde(default)]
+    pub weighting: Weighting,
+}
+
+/// Samples in `[lower, upper)` (the last bin also includes `upper`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub duration_ms: i64,
+    /// Share (0-1) of all samples or of the total time, depending on the weighting.
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Histogram {
+    pub sensor: String,
+    pub weighting: Weighting,
+    pub bins: Vec<HistogramBin>,
+    /// Samples below the first or above the last explicit edge.
+    pub underflow: HistogramBin,
+    pub overflow: HistogramBin,
+    pub total_count: usize,
+    pub total_ms: i64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// How long each sample lasted: until the next sample, capped for gaps; the last sample gets
+/// the typical interval.
+pub fn sample_durations(timestamps: &[i64]) -> Vec<i64> {
+    let step = sample_step(timestamps);
+    let cap = step * MAX_GAP_STEPS;
+    (0..timestamps.len())
+        .map(|i| match timestamps.get(i + 1) {
+            Some(next) => {
+                let d = next - timestamps[i];
+                if d > cap { step } else { d }
+            }
+            None => step,
+        })
+        .collect()
+}
+
+/// Bin edges chosen from the data.
+pub fn auto_edges(values: &[f64], bins: Option<usize>) -> Vec<f64> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if values.is_empty() {
+        return Vec::new();
+    }
+    // Flags (0/1) get a "No" and a "Yes" bin.
+    if bins.is_none() && values.iter().all(|v| *v == 0.0 || *v == 1.0) {
+        return vec![0.0, 0.5, 1.0];
+    }
+    if min == max {
+        return vec![min - 0.5, max + 0.5];
+    }
+
+    let n = bins.unwrap_or_else(|| {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let q = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+        let iqr = q(0.75) - q(0.25);
+        let sturges = (values.len() as f64).log2().ceil() as usize + 1;
+        if iqr > 0.0 {
+            let width = 2.0 * iqr / (values.len() as f64).cbrt();
+            ((max - min) / width).ceil() as usize
+        } else {
+            sturges
+        }
+    });
+    let n = n.clamp(1, MAX_AUTO_BINS);
+    let width = (max - min) / n as f64;
+    (0..=n).map(|i| if i == n { max } else { min + width * i as f64 }).collect()
+}
+
+fn validate_edges(edges: &[f64]) -> Result<(), String> {
+    if edges.len() < 2 {
+        return Err("直方图至少需要两个分箱边界".to_string());
+    }
+    if edges.iter().any(|e| !e.is_finite()) || edges.windows(2).any(|w| w[0] >= w[1]) {
+        return Err("直方图分箱边界必须严格递增".to_string());
+    }
+    Ok(())
+}
+
+/// Index of the bin holding `value`, or `Err(true)` above / `Err(false)` below the edges.
+fn bin_index(edges: &[f64], value: f64) -> Result<usize, bool> {
+    let last = edges.len() - 1;
+    if value < edges[0] {
+        return Err(false);
+    }
+    if value > edges[last] {
+        return Err(true);
+    }
+    // First edge greater than the value closes its bin; the top edge belongs to the last bin.
+    Ok(edges.partition_point(|e| *e <= value).clamp(1, last) - 1)
+}
+
+pub fn histogram(rows: &[DataRow], query: &HistogramQuery) -> Result<Histogram, String> {
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: vec![query.sensor.clone()],
+            start: query.start,
+            end: query.end,
+            ..Default::default()
+        },
+    );
+    let durations = sample_durations(&set.timestamps);
+    let samples: Vec<(f64, i64)> = set.series[0]
+        .values
+        .iter()
+        .zip(&durations)
+        .filter_map(|(v, d)| v.map(|v| (v, *d)))
+        .collect();
+    let values: Vec<f64> = samples.iter().map(|(v, _)| *v).collect();
+
+    let edges = match &query.edges {
+        Some(edges) => {
+            validate_edges(edges)?;
+            edges.clone()
+        }
+        None => auto_edges(&values, query.bins),
+    };
+
+    let mut bins: Vec<HistogramBin> = edges
+        .windows(2)
+        .map(|w| HistogramBin {
+            lower: w[0],
+            upper: w[1],
+            ..Default::default()
+        })
+        .collect();
+    let mut underflow = HistogramBin {
+        lower: f64::NEG_INFINITY,
+        upper: edges.first().copied().unwrap_or(f64::NEG_INFINITY),
+        ..Default::default()
+    };
+    let mut overflow = HistogramBin {
+        lower: edges.last().copied().unwrap_or(f64::INFINITY),
+        upper: f64::INFINITY,
+        ..Default::default()
+    };
+
+    for &(value, duration) in &samples {
+        let bin = match bin_index(&edges, value) {
+            Ok(i) => &mut bins[i],
+            Err(true) => &mut overflow,
+            Err(false) => &mut underflow,
+        };
+        bin.count += 1;
+        bin.duration_ms += duration;
+    }
+
+    let total_count = samples.len();
+    let total_ms: i64 = samples.iter().map(|(_, d)| *d).sum();
+    for bin in bins.iter_mut().chain([&mut underflow, &mut overflow]) {
+        bin.share = match query.weighting {
+            Weighting::Time if total_ms > 0 => bin.duration_ms as f64 / total_ms as f64,
+            Weighting::Count if total_count > 0 => bin.count as f64 / total_count as f64,
+            _ => 0.0,
+        };
+    }
+
+    Ok(Histogram {
+        sensor: query.sensor.clone(),
+        weighting: query.weighting,
+        bins,
+        underflow,
+        overflow,
+        total_count,
+        total_ms,
+        min: values.iter().copied().reduce(f64::min),
+        max: values.iter().copied().reduce(f64::max),
+    })
+}
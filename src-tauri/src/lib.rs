@@ -1,33 +1,236 @@
-use crate::data_processor::DataProcessor;
-use crate::models::{DataGroup, GroupsConfig};
+use crate::aggregate::{AggregateQuery, AggregateTable};
+use crate::anomaly::{AnomalyQuery, AnomalyReport};
+use crate::catalog::SensorInfo;
+use crate::compare::{CompareQuery, ComparisonReport};
+use crate::correlation::{CorrelationMatrix, CorrelationQuery, LagQuery, LagResult};
+use crate::data_processor::{CsvFollower, DataProcessor, FollowUpdate};
+use crate::dataset::{DatasetId, DatasetInfo, DatasetRegistry, MemoryUsage};
+use crate::derived::DerivedEngine;
+use crate::energy::{EnergyQuery, EnergyReport};
+use crate::fancurve::{FanCurveQuery, FanCurveReport};
+use crate::histogram::{Histogram, HistogramQuery};
+use crate::merge::{DataGap, LogPart};
+use crate::models::{DataGroup, DerivedSensor, GroupsConfig};
+use crate::phases::{PhaseQuery, PhaseReport};
+use crate::query::{RowPage, RowQuery, TimeBounds};
+use crate::search::{SearchHit, SearchQuery};
+use crate::series::{SeriesQuery, SeriesSet};
+use crate::stats::{StatsQuery, StatsTable};
+use crate::thermal::{ThermalFit, ThermalFitQuery};
+use crate::thresholds::{Episode, ThresholdRule};
+use crate::throttling::{ThrottlingQuery, ThrottlingReport};
 use backtrace::Backtrace;
 use lazy_static::lazy_static;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::collections::HashMap;
-use std::sync::mpsc::channel;
-use std::sync::RwLock;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+use tauri::AppHandle;
 
+pub mod aggregate;
+pub mod anomaly;
+pub mod catalog;
+pub mod compare;
+pub mod correlation;
 pub mod data_processor;
+pub mod dataset;
+pub mod derived;
+pub mod downsample;
+pub mod energy;
+pub mod events;
+pub mod expr;
+pub mod fancurve;
+pub mod histogram;
+pub mod merge;
 pub mod models;
+pub mod phases;
+pub mod pinyin;
+pub mod query;
+pub mod resample;
+pub mod search;
+pub mod series;
+pub mod smoothing;
+pub mod stats;
+pub mod thermal;
+pub mod thresholds;
+pub mod throttling;
+pub mod watcher;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 
+/// Load a CSV as a new dataset and return its ID.
 #[tauri::command]
-fn load_csv(path: String) -> Result<(), String> {
+fn load_csv(app: AppHandle, path: String) -> Result<DatasetId, String> {
+    open_csv(&app, path)
+}
+
+/// Merge several logs (e.g. from restarts of HWiNFO during one test) into a single dataset
+/// and return its ID. The merged dataset is not followed.
+#[tauri::command]
+fn merge_csv_logs(app: AppHandle, paths: Vec<String>) -> Result<DatasetId, String> {
+    if paths.is_empty() {
+        return Err("请至少选择一个日志文件".to_string());
+    }
+    let processor = current_processor()?;
+
+    let mut parts = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let (rows, follower) = processor.follow_csv_file(path).map_err(|e| {
+            log::error!("CSV 处理失败, path={:?}, err={:?}", path, e);
+            format!("CSV 处理失败（{path}）: {e}")
+        })?;
+        parts.push(LogPart {
+            path: path.clone(),
+            columns: follower.headers().to_vec(),
+            rows,
+        });
+    }
+
+    let merged = merge::merge_logs(parts);
+    let path = merged
+        .sources
+        .iter()
+        .map(|s| s.path.as_str())
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let row_count = merged.rows.len();
+    log::info!("已合并 {} 个日志, 间隔 {} 处, 共 {} 行", paths.len(), merged.gaps.len(), row_count);
+    let dataset_id = write_datasets()?.insert_merged(path.clone(), merged);
+    events::emit(
+        &app,
+        events::DATASET_LOADED,
+        events::DatasetLoaded {
+            dataset_id,
+            path,
+            row_count,
+        },
+    );
+    Ok(dataset_id)
+}
+
+/// Pauses between the logs of a merged dataset (empty for a single log).
+#[tauri::command]
+fn get_dataset_gaps(dataset_id: DatasetId) -> Result<Vec<DataGap>, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(dataset.gaps.clone())
+}
+
+/// Watch a folder for HWiNFO logs: open the newest valid CSV now and open newer ones as
+/// HWiNFO starts new logging sessions. Unless `keep_previous` is set, the log opened by the
+/// watch before is closed when a newer one appears. Returns the dataset that was opened, if any.
+#[tauri::command]
+fn watch_log_folder(
+    app: AppHandle,
+    path: String,
+    keep_previous: Option<bool>,
+) -> Result<Option<DatasetInfo>, String> {
+    let dir = PathBuf::from(&path);
+    if !dir.is_dir() {
+        return Err(format!("日志目录不存在: {path}"));
+    }
+    let keep_previous = keep_previous.unwrap_or(false);
+
+    let newest = watcher::newest_hwinfo_log(&dir)
+        .map_err(|e| format!("读取日志目录失败（{path}）: {e}"))?;
+    let opened = match &newest {
+        // A log that fails to parse is reported, but the folder is still watched for new ones.
+        Some(log_path) => match open_csv(&app, log_path.to_string_lossy().to_string()) {
+            Ok(id) => read_datasets()?.get(id).map(|d| d.info()),
+            Err(e) => {
+                emit_open_error(&app, log_path.to_string_lossy().to_string(), e);
+                None
+            }
+        },
+        None => {
+            log::info!("日志目录中暂无有效的 HWiNFO 日志，等待新文件: {:?}", path);
+            None
+        }
+    };
+
+    let mut current_id = opened.as_ref().map(|d| d.id);
+    watcher::start_folder_watch(dir, newest, move |new_log| {
+        let new_log = new_log.to_string_lossy().to_string();
+        match open_csv(&app, new_log.clone()) {
+            Ok(id) => {
+                if let Some(prev) = current_id.replace(id) {
+                    if !keep_previous {
+                        close_dataset_and_notify(&app, prev);
+                    }
+                }
+            }
+            Err(e) => emit_open_error(&app, new_log, e),
+        }
+    });
+    Ok(opened)
+}
+
+fn emit_open_error(app: &AppHandle, path: String, message: String) {
+    log::error!("自动打开日志失败: {message}");
+    events::emit(
+        app,
+        events::PARSE_ERROR,
+        events::ParseError {
+            dataset_id: None,
+            path,
+            message,
+        },
+    );
+}
+
+#[tauri::command]
+fn stop_log_folder_watch() {
+    watcher::stop_folder_watch();
+}
+
+#[tauri::command]
+fn list_datasets() -> Result<Vec<DatasetInfo>, String> {
+    Ok(read_datasets()?.list())
+}
+
+/// Drop a dataset from memory; its file watcher stops on its own.
+#[tauri::command]
+fn close_dataset(app: AppHandle, dataset_id: DatasetId) -> Result<(), String> {
+    if !read_datasets()?.contains(dataset_id) {
+        return Err(dataset_not_found(dataset_id));
+    }
+    close_dataset_and_notify(&app, dataset_id);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_memory_usage() -> Result<MemoryUsage, String> {
+    Ok(read_datasets()?.memory_usage())
+}
+
+/// Parse a CSV into a new dataset and start following it.
+fn open_csv(app: &AppHandle, path: String) -> Result<DatasetId, String> {
     log::info!("Starting CSV processing..., path: {:?}", path);
-    let config = GLOBAL_CONFIG
-        .read()
-        .map_err(|e| format!("读取配置失败（锁已污染）: {e}"))?
-        .clone();
-    let processor = DataProcessor::new(config);
-    match processor.process_csv_file(&path) {
-        Ok(result) => {
-            *GLOBAL_CACHE
-                .write()
-                .map_err(|e| format!("更新缓存失败（锁已污染）: {e}"))? = result;
-            start_csv_watcher(path.clone());
-            log::info!("CSV processed successfully: {:?}", path);
-            Ok(())
+    let generation = CONFIG_GENERATION.load(Ordering::SeqCst);
+    let processor = current_processor()?;
+    match processor.follow_csv_file(&path) {
+        Ok((result, follower)) => {
+            let row_count = result.len();
+            let columns = follower.headers().to_vec();
+            let dataset_id = write_datasets()?.insert_with_columns(path.clone(), columns, result);
+            start_csv_watcher(app.clone(), dataset_id, generation, processor, follower);
+            log::info!("CSV processed successfully: {:?}, dataset_id={}", path, dataset_id);
+            events::emit(
+                app,
+                events::DATASET_LOADED,
+                events::DatasetLoaded {
+                    dataset_id,
+                    path,
+                    row_count,
+                },
+            );
+            Ok(dataset_id)
         }
         Err(e) => {
             log::error!("CSV 处理失败, path={:?}, err={:?}", path, e);
@@ -36,30 +239,77 @@ fn load_csv(path: String) -> Result<(), String> {
     }
 }
 
-#[tauri::command]
-fn get_data() -> Result<String, String> {
-    let cache = GLOBAL_CACHE
+/// Processor built from the current groups.toml.
+fn current_processor() -> Result<DataProcessor, String> {
+    let config = GLOBAL_CONFIG
         .read()
-        .map_err(|e| format!("读取缓存失败（锁已污染）: {e}"))?
+        .map_err(|e| format!("读取配置失败（锁已污染）: {e}"))?
         .clone();
-    serde_json::to_string(&cache).map_err(|e| {
+    Ok(DataProcessor::new(config))
+}
+
+fn close_dataset_and_notify(app: &AppHandle, dataset_id: DatasetId) {
+    let removed = match write_datasets() {
+        Ok(mut datasets) => datasets.remove(dataset_id),
+        Err(e) => {
+            log::error!("{e}");
+            return;
+        }
+    };
+    if let Some(dataset) = removed {
+        log::info!("数据集已关闭: id={}, path={:?}", dataset_id, dataset.path);
+        events::emit(
+            app,
+            events::DATASET_CLOSED,
+            events::DatasetClosed {
+                dataset_id,
+                path: dataset.path,
+            },
+        );
+    }
+}
+
+fn read_datasets() -> Result<RwLockReadGuard<'static, DatasetRegistry>, String> {
+    DATASETS
+        .read()
+        .map_err(|e| format!("读取数据集失败（锁已污染）: {e}"))
+}
+
+fn write_datasets() -> Result<RwLockWriteGuard<'static, DatasetRegistry>, String> {
+    DATASETS
+        .write()
+        .map_err(|e| format!("更新数据集失败（锁已污染）: {e}"))
+}
+
+fn dataset_not_found(dataset_id: DatasetId) -> String {
+    format!("数据集不存在: {dataset_id}")
+}
+
+#[tauri::command]
+fn get_data(dataset_id: DatasetId) -> Result<String, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    serde_json::to_string(&dataset.rows).map_err(|e| {
         log::error!("序列化缓存失败: {e}");
         format!("序列化缓存失败: {e}")
     })
 }
 
 #[tauri::command]
-fn get_data_by_key(key: String) -> Result<String, String> {
+fn get_data_by_key(dataset_id: DatasetId, key: String) -> Result<String, String> {
     if key.trim().is_empty() {
         return Err("key 不能为空".to_string());
     }
 
-    let cache = GLOBAL_CACHE
-        .read()
-        .map_err(|e| format!("读取缓存失败（锁已污染）: {e}"))?;
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
     let mut result: Vec<DataGroup> = Vec::new();
 
-    for group in cache.iter() {
+    for group in dataset.rows.iter() {
         // println!("Group: {:?}, Key: {:?}", group, key);
         result.extend(find_key_in_group(group, &key));
     }
@@ -70,6 +320,350 @@ fn get_data_by_key(key: String) -> Result<String, String> {
     })
 }
 
+/// Rows inside a time window, optionally narrowed to some sensors and paginated.
+#[tauri::command]
+fn query_rows(dataset_id: DatasetId, query: RowQuery) -> Result<RowPage, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(query::query_rows(&dataset.rows, &query))
+}
+
+/// Sensors as parallel timestamp/value arrays, the compact format used by the charts.
+#[tauri::command]
+fn get_series(dataset_id: DatasetId, query: SeriesQuery) -> Result<SeriesSet, String> {
+    if query.sensors.iter().any(|s| s.trim().is_empty()) {
+        return Err("传感器名称不能为空".to_string());
+    }
+    if let Some(spec) = &query.resample {
+        spec.validate()?;
+    }
+    for smoothing in query.smoothing.iter().flatten() {
+        smoothing.validate()?;
+    }
+
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(series::extract_series(&dataset.rows, &query))
+}
+
+/// Export sensor series (optionally resampled to a fixed interval) as CSV.
+#[tauri::command]
+fn export_series_csv(dataset_id: DatasetId, query: SeriesQuery, path: String) -> Result<(), String> {
+    let set = get_series(dataset_id, query)?;
+    let csv = set.to_csv().map_err(|e| format!("生成序列 CSV 失败: {e}"))?;
+    std::fs::write(&path, csv).map_err(|e| {
+        log::error!("写入序列文件失败（{:?}）: {e}", path);
+        format!("写入序列文件失败: {e}")
+    })
+}
+
+/// Descriptive statistics for a set of sensors over an optional time window.
+#[tauri::command]
+fn get_sensor_stats(dataset_id: DatasetId, query: StatsQuery) -> Result<StatsTable, String> {
+    if let Some(spec) = &query.resample {
+        spec.validate()?;
+    }
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(stats::sensor_stats(&dataset.rows, &query))
+}
+
+#[tauri::command]
+fn export_sensor_stats(dataset_id: DatasetId, query: StatsQuery, path: String) -> Result<(), String> {
+    let table = get_sensor_stats(dataset_id, query)?;
+    let csv = table.to_csv().map_err(|e| format!("生成统计 CSV 失败: {e}"))?;
+    std::fs::write(&path, csv).map_err(|e| {
+        log::error!("写入统计文件失败（{:?}）: {e}", path);
+        format!("写入统计文件失败: {e}")
+    })
+}
+
+fn apply_runtime_derived(sensors: &[DerivedSensor], rows: &mut [models::DataRow]) {
+    if sensors.is_empty() {
+        return;
+    }
+    match DerivedEngine::new(sensors) {
+        Ok(engine) => engine.apply(rows),
+        Err(e) => log::error!("应用派生传感器失败: {e}"),
+    }
+}
+
+/// Add a derived sensor to a loaded dataset; it is evaluated for all current and future rows.
+#[tauri::command]
+fn add_derived_sensor(app: AppHandle, dataset_id: DatasetId, sensor: DerivedSensor) -> Result<(), String> {
+    let engine = DerivedEngine::new(std::slice::from_ref(&sensor))?;
+
+    let mut datasets = write_datasets()?;
+    let dataset = datasets
+        .get_mut(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    if dataset.has_sensor(&sensor.name) {
+        return Err(format!("传感器「{}」已存在", sensor.name));
+    }
+
+    engine.apply(&mut dataset.rows);
+    dataset.derived.push(sensor);
+    let (path, row_count) = (dataset.path.clone(), dataset.rows.len());
+    drop(datasets);
+
+    // The sensor list changed; let the UI refresh like after a reload.
+    events::emit(
+        &app,
+        events::DATASET_LOADED,
+        events::DatasetLoaded {
+            dataset_id,
+            path,
+            row_count,
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+fn remove_derived_sensor(app: AppHandle, dataset_id: DatasetId, name: String) -> Result<(), String> {
+    let mut datasets = write_datasets()?;
+    let dataset = datasets
+        .get_mut(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    let index = dataset
+        .derived
+        .iter()
+        .position(|s| s.name == name)
+        .ok_or_else(|| format!("派生传感器不存在: {name}"))?;
+
+    let sensor = dataset.derived.remove(index);
+    derived::remove_from_rows(&mut dataset.rows, &sensor);
+    let (path, row_count) = (dataset.path.clone(), dataset.rows.len());
+    drop(datasets);
+
+    events::emit(
+        &app,
+        events::DATASET_LOADED,
+        events::DatasetLoaded {
+            dataset_id,
+            path,
+            row_count,
+        },
+    );
+    Ok(())
+}
+
+/// Derived sensors of a dataset: the ones from groups.toml followed by the runtime ones.
+#[tauri::command]
+fn list_derived_sensors(dataset_id: DatasetId) -> Result<Vec<DerivedSensor>, String> {
+    let configured = GLOBAL_CONFIG
+        .read()
+        .map_err(|e| format!("读取配置失败（锁已污染）: {e}"))?
+        .derived_sensors
+        .clone();
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(configured.into_iter().chain(dataset.derived.iter().cloned()).collect())
+}
+
+/// Intervals where a threshold rule held, e.g. CPU package above 90 °C for at least 10 s.
+#[tauri::command]
+fn detect_threshold_episodes(
+    dataset_id: DatasetId,
+    rule: ThresholdRule,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<Vec<Episode>, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(thresholds::detect_episodes(&dataset.rows, &rule, start, end))
+}
+
+/// Replace the live threshold monitors of a dataset. While the log is followed, every episode
+/// that ends is pushed as a `threshold-episode` event.
+#[tauri::command]
+fn set_threshold_monitors(dataset_id: DatasetId, rules: Vec<ThresholdRule>) -> Result<(), String> {
+    let mut datasets = write_datasets()?;
+    let dataset = datasets
+        .get_mut(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    dataset.monitors = thresholds::primed_detectors(&rules, &dataset.rows);
+    Ok(())
+}
+
+/// Pearson or Spearman correlation between every pair of the selected sensors.
+#[tauri::command]
+fn get_correlation_matrix(dataset_id: DatasetId, query: CorrelationQuery) -> Result<CorrelationMatrix, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(correlation::correlation_matrix(&dataset.rows, &query))
+}
+
+/// Cross-correlation of two sensors to estimate how long one trails the other.
+#[tauri::command]
+fn get_sensor_lag(dataset_id: DatasetId, query: LagQuery) -> Result<LagResult, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(correlation::lag_analysis(&dataset.rows, &query))
+}
+
+/// Distribution of one sensor, weighted by time residency or by sample count.
+#[tauri::command]
+fn get_histogram(dataset_id: DatasetId, query: HistogramQuery) -> Result<Histogram, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    histogram::histogram(&dataset.rows, &query)
+}
+
+/// Sensors summarized per time bucket (e.g. one row per minute of an overnight log).
+#[tauri::command]
+fn get_time_buckets(dataset_id: DatasetId, query: AggregateQuery) -> Result<AggregateTable, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    aggregate::aggregate_buckets(&dataset.rows, &query)
+}
+
+#[tauri::command]
+fn export_time_buckets(dataset_id: DatasetId, query: AggregateQuery, path: String) -> Result<(), String> {
+    let table = get_time_buckets(dataset_id, query)?;
+    let csv = table.to_csv().map_err(|e| format!("生成聚合 CSV 失败: {e}"))?;
+    std::fs::write(&path, csv).map_err(|e| {
+        log::error!("写入聚合文件失败（{:?}）: {e}", path);
+        format!("写入聚合文件失败: {e}")
+    })
+}
+
+/// Spikes, outliers, step changes and dropouts across sensors, for jumping to the
+/// interesting moments of a long log.
+#[tauri::command]
+fn detect_anomalies(dataset_id: DatasetId, query: AnomalyQuery) -> Result<AnomalyReport, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    anomaly::detect_anomalies(&dataset.rows, &query)
+}
+
+/// Idle, light, full-load and cooldown phases with per-phase statistics.
+#[tauri::command]
+fn get_load_phases(dataset_id: DatasetId, query: PhaseQuery) -> Result<PhaseReport, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    phases::segment_phases(&dataset.rows, &query)
+}
+
+/// Energy of power sensors by trapezoidal integration, optionally per load phase.
+#[tauri::command]
+fn get_energy(dataset_id: DatasetId, query: EnergyQuery) -> Result<EnergyReport, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    energy::integrate_energy(&dataset.rows, &query)
+}
+
+/// First-order fit (time constant, steady state, time to 95 %) of a temperature after a
+/// load step.
+#[tauri::command]
+fn fit_thermal_response(dataset_id: DatasetId, query: ThermalFitQuery) -> Result<ThermalFit, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    thermal::fit_thermal_response(&dataset.rows, &query)
+}
+
+/// Fan/pump response against a temperature: scatter, piecewise-linear curve, hysteresis and
+/// hunting episodes.
+#[tauri::command]
+fn get_fan_curve(dataset_id: DatasetId, query: FanCurveQuery) -> Result<FanCurveReport, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    fancurve::fan_curve(&dataset.rows, &query)
+}
+
+/// A/B comparison of two datasets aligned on elapsed time or a sync event. `baseline_id` is
+/// run A; deltas are candidate minus baseline.
+#[tauri::command]
+fn compare_datasets(
+    baseline_id: DatasetId,
+    candidate_id: DatasetId,
+    query: CompareQuery,
+) -> Result<ComparisonReport, String> {
+    if let Some(spec) = &query.resample {
+        spec.validate()?;
+    }
+    let datasets = read_datasets()?;
+    let baseline = datasets
+        .get(baseline_id)
+        .ok_or_else(|| dataset_not_found(baseline_id))?;
+    let candidate = datasets
+        .get(candidate_id)
+        .ok_or_else(|| dataset_not_found(candidate_id))?;
+    compare::compare_runs(&baseline.rows, &candidate.rows, &query)
+}
+
+/// Throttling and performance-limiter flags turned into intervals and summarized per device.
+#[tauri::command]
+fn get_throttling_summary(dataset_id: DatasetId, query: ThrottlingQuery) -> Result<ThrottlingReport, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(throttling::throttling_summary(&dataset.rows, &query))
+}
+
+/// Every sensor of a dataset with its group path, unit, value type and coverage.
+#[tauri::command]
+fn get_sensor_catalog(dataset_id: DatasetId) -> Result<Vec<SensorInfo>, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(catalog::build_catalog(&dataset.rows, &dataset.columns))
+}
+
+/// Substring, regex or fuzzy (incl. pinyin) search over the sensors of a dataset.
+#[tauri::command]
+fn search_sensors(dataset_id: DatasetId, query: SearchQuery) -> Result<Vec<SearchHit>, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    let sensors = dataset
+        .rows
+        .first()
+        .map(series::sensor_fields)
+        .unwrap_or_default();
+    search::search_sensors(&sensors, &query).map_err(|e| format!("正则表达式无效: {e}"))
+}
+
+#[tauri::command]
+fn get_time_bounds(dataset_id: DatasetId) -> Result<Option<TimeBounds>, String> {
+    let datasets = read_datasets()?;
+    let dataset = datasets
+        .get(dataset_id)
+        .ok_or_else(|| dataset_not_found(dataset_id))?;
+    Ok(query::time_bounds(&dataset.rows))
+}
+
 pub fn find_key_in_group(group: &HashMap<String, DataGroup>, key: &str) -> Vec<DataGroup> {
     let mut found = Vec::new();
     
@@ -117,11 +711,24 @@ lazy_static! {
         let cfg = GroupsConfig::load_from_file("config/groups.toml").expect("加载配置失败");
         RwLock::new(cfg)
     };
-    static ref GLOBAL_CACHE: RwLock<Vec<HashMap<String, DataGroup>>> = RwLock::new(Vec::new());
+    static ref DATASETS: RwLock<DatasetRegistry> = RwLock::new(DatasetRegistry::new());
 }
 
-fn start_csv_watcher(csv_path: String) {
+/// Bumped on every groups.toml reload; followed datasets re-parse their log when it changes.
+static CONFIG_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Follow a dataset's CSV while HWiNFO keeps appending to it, parsing only the new rows.
+/// `processor` was built from the config of `generation`; when groups.toml is reloaded later,
+/// it is rebuilt and the log re-parsed from scratch. The thread exits once the dataset is closed.
+fn start_csv_watcher(
+    app: AppHandle,
+    dataset_id: DatasetId,
+    mut generation: u64,
+    mut processor: DataProcessor,
+    mut follower: CsvFollower,
+) {
     std::thread::spawn(move || {
+        let csv_path = follower.path().to_string();
         let (tx, rx) = channel();
         let mut watcher: RecommendedWatcher = match RecommendedWatcher::new(tx, notify::Config::default()) {
             Ok(w) => w,
@@ -136,38 +743,56 @@ fn start_csv_watcher(csv_path: String) {
             return;
         }
 
-        for res in rx {
+        loop {
+            let latest = CONFIG_GENERATION.load(Ordering::SeqCst);
+            if latest != generation {
+                generation = latest;
+                // 分组配置已变化，用新配置重新完整解析
+                match current_processor() {
+                    Ok(fresh) => processor = fresh,
+                    Err(err) => log::error!("{err}"),
+                }
+                match processor.follow_csv_file(&csv_path) {
+                    Ok((rows, new_follower)) => {
+                        log::info!("配置已变化，重新解析 CSV: {:?}", csv_path);
+                        follower = new_follower;
+                        let update = FollowUpdate::Reloaded(rows);
+                        if apply_follow_update(&app, dataset_id, &follower, update).is_break() {
+                            return;
+                        }
+                    }
+                    Err(err) => {
+                        let message = format!("CSV 重新解析失败（{csv_path}）: {err}");
+                        emit_follow_error(&app, dataset_id, &csv_path, &message);
+                    }
+                }
+            }
+
+            let res = match rx.recv_timeout(Duration::from_secs(1)) {
+                Ok(res) => res,
+                Err(RecvTimeoutError::Timeout) => {
+                    if !DATASETS.read().map(|d| d.contains(dataset_id)).unwrap_or(false) {
+                        log::info!("数据集已关闭，停止监听: {:?}", csv_path);
+                        return;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+
             match res {
                 Ok(event) if event.kind.is_modify() => {
-                    for path in &event.paths {
-                        if let Some(p) = path.to_str() {
-                            log::info!("检测到 CSV 更新: {:?}", p);
-                            // 重新处理 CSV 并更新缓存
-                            let config = match GLOBAL_CONFIG.read() {
-                                Ok(cfg) => cfg.clone(),
-                                Err(e) => {
-                                    log::error!("读取配置失败（锁已污染），跳过本次重载: {e}");
-                                    continue;
-                                }
-                            };
-
-                            match DataProcessor::new(config).process_csv_file(p) {
-                                Ok(result) => {
-                                    match GLOBAL_CACHE.write() {
-                                        Ok(mut cache) => {
-                                            *cache = result;
-                                            log::info!("CSV 缓存已更新");
-                                        }
-                                        Err(e) => {
-                                            log::error!("更新缓存失败（锁已污染）: {e}");
-                                        }
-                                    }
-                                }
-                                Err(err) => {
-                                    log::error!("CSV 重新处理失败（{}）: {err}", p);
-                                }
+                    // 只解析新追加的行；截断或写入尾部元数据时由 follower 自动完整重载
+                    match processor.poll_appended(&mut follower) {
+                        Ok(update) => {
+                            if apply_follow_update(&app, dataset_id, &follower, update).is_break() {
+                                return;
                             }
                         }
+                        Err(err) => {
+                            let message = format!("CSV 增量解析失败（{csv_path}）: {err}");
+                            emit_follow_error(&app, dataset_id, &csv_path, &message);
+                        }
                     }
                 }
                 Err(e) => {
@@ -179,7 +804,92 @@ fn start_csv_watcher(csv_path: String) {
     });
 }
 
-fn start_config_watcher() {
+fn emit_follow_error(app: &AppHandle, dataset_id: DatasetId, csv_path: &str, message: &str) {
+    log::error!("{message}");
+    events::emit(
+        app,
+        events::PARSE_ERROR,
+        events::ParseError {
+            dataset_id: Some(dataset_id),
+            path: csv_path.to_string(),
+            message: message.to_string(),
+        },
+    );
+}
+
+/// Store the rows of a followed log in its dataset and notify the frontend.
+/// Breaks once the dataset has been closed.
+fn apply_follow_update(
+    app: &AppHandle,
+    dataset_id: DatasetId,
+    follower: &CsvFollower,
+    update: FollowUpdate,
+) -> ControlFlow<()> {
+    let csv_path = follower.path();
+    let mut datasets = match DATASETS.write() {
+        Ok(d) => d,
+        Err(e) => {
+            log::error!("更新数据集失败（锁已污染）: {e}");
+            return ControlFlow::Continue(());
+        }
+    };
+    let Some(dataset) = datasets.get_mut(dataset_id) else {
+        log::info!("数据集已关闭，停止监听: {:?}", csv_path);
+        return ControlFlow::Break(());
+    };
+
+    match update {
+        FollowUpdate::Appended(rows) if rows.is_empty() => {}
+        FollowUpdate::Appended(mut rows) => {
+            log::info!("CSV 追加 {} 行", rows.len());
+            apply_runtime_derived(&dataset.derived, &mut rows);
+            let episodes = thresholds::feed_rows(&mut dataset.monitors, &rows);
+            let start_index = dataset.rows.len();
+            dataset.rows.extend(rows.iter().cloned());
+            drop(datasets);
+            for episode in episodes {
+                events::emit(
+                    app,
+                    events::THRESHOLD_EPISODE,
+                    events::ThresholdEpisode { dataset_id, episode },
+                );
+            }
+            events::emit(
+                app,
+                events::ROWS_APPENDED,
+                events::RowsAppended {
+                    dataset_id,
+                    path: csv_path.to_string(),
+                    start_index,
+                    rows,
+                },
+            );
+        }
+        FollowUpdate::Reloaded(mut rows) => {
+            apply_runtime_derived(&dataset.derived, &mut rows);
+            let row_count = rows.len();
+            let rules: Vec<ThresholdRule> =
+                dataset.monitors.iter().map(|m| m.rule().clone()).collect();
+            dataset.monitors = thresholds::primed_detectors(&rules, &rows);
+            dataset.rows = rows;
+            dataset.columns = follower.headers().to_vec();
+            drop(datasets);
+            log::info!("CSV 缓存已重新加载");
+            events::emit(
+                app,
+                events::DATASET_LOADED,
+                events::DatasetLoaded {
+                    dataset_id,
+                    path: csv_path.to_string(),
+                    row_count,
+                },
+            );
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+fn start_config_watcher(app: AppHandle) {
     let (tx, rx) = channel();
     let mut watcher: RecommendedWatcher = match RecommendedWatcher::new(tx, notify::Config::default()) {
         Ok(w) => w,
@@ -195,6 +905,8 @@ fn start_config_watcher() {
     }
 
     std::thread::spawn(move || {
+        // Keep the watcher alive for as long as this thread runs; dropping it stops the events.
+        let _watcher = watcher;
         for res in rx {
             match res {
                 Ok(event) if event.kind.is_modify() => {
@@ -205,7 +917,17 @@ fn start_config_watcher() {
                                 Ok(new_cfg) => match GLOBAL_CONFIG.write() {
                                     Ok(mut cfg) => {
                                         *cfg = new_cfg;
+                                        drop(cfg);
+                                        // Followed datasets pick this up and re-parse their logs.
+                                        CONFIG_GENERATION.fetch_add(1, Ordering::SeqCst);
                                         log::info!("配置已重新加载");
+                                        events::emit(
+                                            &app,
+                                            events::CONFIG_RELOADED,
+                                            events::ConfigReloaded {
+                                                path: "config/groups.toml".to_string(),
+                                            },
+                                        );
                                     }
                                     Err(e) => {
                                         log::error!("写入新配置失败（锁已污染）: {e}");
@@ -248,14 +970,46 @@ pub fn run() {
         )
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
-        .setup(|_| {
-            start_config_watcher();
+        .setup(|app| {
+            start_config_watcher(app.handle().clone());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_data,
             get_data_by_key,
-            load_csv
+            load_csv,
+            watch_log_folder,
+            stop_log_folder_watch,
+            list_datasets,
+            close_dataset,
+            get_memory_usage,
+            query_rows,
+            get_time_bounds,
+            get_series,
+            get_sensor_stats,
+            export_sensor_stats,
+            export_series_csv,
+            search_sensors,
+            get_sensor_catalog,
+            add_derived_sensor,
+            remove_derived_sensor,
+            list_derived_sensors,
+            detect_threshold_episodes,
+            set_threshold_monitors,
+            get_throttling_summary,
+            get_correlation_matrix,
+            get_sensor_lag,
+            get_histogram,
+            get_time_buckets,
+            export_time_buckets,
+            detect_anomalies,
+            get_load_phases,
+            get_energy,
+            fit_thermal_response,
+            get_fan_curve,
+            merge_csv_logs,
+            get_dataset_gaps,
+            compare_datasets
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,129 @@
+use serde::Serialize;
+
+use crate::dataset::row_timestamp;
+use crate::models::{DataGroup, DataRow};
+use crate::series::{field_at, sensor_fields};
+
+/// One parsed log to merge.
+#[derive(Debug, Clone)]
+pub struct LogPart {
+    pub path: String,
+    /// Column names in CSV order.
+    pub columns: Vec<String>,
+    pub rows: Vec<DataRow>,
+}
+
+/// Time between the last row of one log and the first row of the next one. Logs that
+/// overlap in time leave no gap.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataGap {
+    pub start: i64,
+    pub end: i64,
+    pub duration_ms: i64,
+    /// Log before and after the gap.
+    pub before: String,
+    pub after: String,
+}
+
+/// Where each log ended up in the merged dataset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSource {
+    pub path: String,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub row_count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MergedLog {
+    /// Union of the column sets, in order of first appearance.
+    pub columns: Vec<String>,
+    pub rows: Vec<DataRow>,
+    pub gaps: Vec<DataGap>,
+    pub sources: Vec<MergeSource>,
+}
+
+fn first_timestamp(rows: &[DataRow]) -> Option<i64> {
+    rows.iter().find_map(row_timestamp)
+}
+
+fn last_timestamp(rows: &[DataRow]) -> Option<i64> {
+    rows.iter().rev().find_map(row_timestamp)
+}
+
+/// Concatenate logs in order of their first timestamp (interleaving rows when logs overlap).
+///
+/// Sensors missing from some logs are added to their rows as empty values, so every row
+/// has the same layout (which the catalog and series extraction take from the first row) and
+/// the missing stretches read as gaps. Every pause between two logs is recorded as a
+/// [`DataGap`].
+pub fn merge_logs(mut parts: Vec<LogPart>) -> MergedLog {
+    parts.sort_by_key(|p| first_timestamp(&p.rows).unwrap_or(i64::MAX));
+
+    let mut merged = MergedLog::default();
+    let mut fields: Vec<(Vec<String>, String)> = Vec::new();
+    for part in &parts {
+        // HWiNFO repeats names across devices (e.g. two drives), so union by occurrence count.
+        for (i, column) in part.columns.iter().enumerate() {
+            let nth = part.columns[..i].iter().filter(|c| *c == column).count();
+            if merged.columns.iter().filter(|c| *c == column).count() <= nth {
+                merged.columns.push(column.clone());
+            }
+        }
+        if let Some(first) = part.rows.first() {
+            for field in sensor_fields(first) {
+                if !fields.contains(&field) {
+                    fields.push(field);
+                }
+            }
+        }
+    }
+
+    let mut previous: Option<(String, Option<i64>)> = None;
+    let mut overlapping = false;
+    for part in parts {
+        let (start, end) = (first_timestamp(&part.rows), last_timestamp(&part.rows));
+        if let (Some((before, Some(prev_end))), Some(start)) = (&previous, start) {
+            if start <= *prev_end {
+                overlapping = true;
+            } else {
+                merged.gaps.push(DataGap {
+                    start: *prev_end,
+                    end: start,
+                    duration_ms: start - prev_end,
+                    before: before.clone(),
+                    after: part.path.clone(),
+                });
+            }
+        }
+
+        merged.sources.push(MergeSource {
+            path: part.path.clone(),
+            start,
+            end,
+            row_count: part.rows.len(),
+        });
+        let prev_end = previous.and_then(|(_, e)| e);
+        previous = Some((part.path, end.max(prev_end)));
+
+        for mut row in part.rows {
+            for (path, sensor) in &fields {
+                if field_at(&row, path, sensor).is_none() {
+                    row.entry(path[0].clone())
+                        .or_insert_with(DataGroup::new)
+                        .insert(&path[1..], sensor, String::new());
+                }
+            }
+            merged.rows.push(row);
+        }
+    }
+
+    if overlapping {
+        // Interleave the rows of overlapping logs; the sort is stable, so equal timestamps keep
+        // the order of their logs.
+        merged.rows.sort_by_key(|row| row_timestamp(row).unwrap_or(i64::MIN));
+    }
+    merged
+}
@@ -45,10 +45,34 @@ impl FieldGroup {
     }
 }
 
+fn default_derived_group() -> Vec<String> {
+    vec!["Derived".to_string()]
+}
+
+/// A sensor computed per row from other sensors, e.g.
+///
+/// ```toml
+/// [[derived_sensors]]
+/// name = "System Power [W]"
+/// expr = "`CPU Package Power [W]` + `GPU Power [W]`"
+/// group = ["System"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, serde::Serialize)]
+pub struct DerivedSensor {
+    pub name: String,
+    /// Expression, see [`crate::expr::Expr`].
+    pub expr: String,
+    /// Group path the sensor is placed under.
+    #[serde(default = "default_derived_group")]
+    pub group: Vec<String>,
+}
+
 /// GroupsConfig save all top groups
 #[derive(Debug, Clone)]
 pub struct GroupsConfig {
     pub groups: HashMap<String, FieldGroup>,
+    /// `[[derived_sensors]]` entries, evaluated in order (later ones may use earlier ones).
+    pub derived_sensors: Vec<DerivedSensor>,
 }
 
 impl std::fmt::Display for GroupsConfig {
@@ -71,16 +95,33 @@ impl GroupsConfig {
     /// Load configuration from a TOML file
     pub fn load_from_file(file_path: &str) -> Result<Self, Box<dyn Error>> {
         let content = fs::read_to_string(file_path)?;
-        // Directly deserialize the TOML top level into HashMap<String, RawGroup>
-        let raw_map: HashMap<String, RawGroup> = toml::from_str(&content)?;
+        let mut table: toml::Table = toml::from_str(&content)?;
+
+        // `derived_sensors` is the only top-level key that is not a group.
+        let derived_sensors: Vec<DerivedSensor> = match table.remove("derived_sensors") {
+            Some(value) => value.try_into()?,
+            None => Vec::new(),
+        };
+        for sensor in &derived_sensors {
+            crate::derived::validate(sensor)?;
+        }
+
+        // Deserialize the remaining TOML top level into HashMap<String, RawGroup>
+        let raw_map: HashMap<String, RawGroup> = toml::Value::Table(table).try_into()?;
         let groups = raw_map
             .into_iter()
             .map(|(name, raw)| (name, FieldGroup::from_raw(&raw)))
             .collect();
-        Ok(GroupsConfig { groups })
+        Ok(GroupsConfig {
+            groups,
+            derived_sensors,
+        })
     }
 }
 
+/// One processed CSV row: top-level group name -> group data
+pub type DataRow = HashMap<String, DataGroup>;
+
 /// save processed data
 #[derive(Debug, serde::Serialize, Clone)]
 pub struct DataGroup {
@@ -0,0 +1,384 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::parse_unit;
+use crate::models::DataRow;
+use crate::series::{extract_series, sensor_fields, SeriesQuery};
+use crate::smoothing::{smooth, Smoothing};
+use crate::stats::{describe, percentile, SensorStats};
+
+/// Load sensors tried, in order, when the query does not name one.
+const DRIVER_CANDIDATES: &[&str] = &[
+    "Total CPU Usage [%]",
+    "CPU 总使用率 [%]",
+    "CPU Package Power [W]",
+    "CPU 封装功率 [W]",
+    "GPU Core Load [%]",
+    "GPU 核心负载 [%]",
+    "GPU Power [W]",
+    "GPU 功率 [W]",
+];
+
+fn default_min_duration_ms() -> i64 {
+    10_000
+}
+
+fn default_smoothing_ms() -> i64 {
+    5_000
+}
+
+fn default_cooldown_ms() -> i64 {
+    120_000
+}
+
+/// Segmentation request. Without explicit thresholds, utilization sensors (`%`) use 10 % and
+/// 80 %, other sensors (e.g. power) 20 % and 60 % of their P1-P99 range.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseQuery {
+    /// Utilization or power sensor driving the segmentation.
+    pub sensor: Option<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub idle_below: Option<f64>,
+    pub load_above: Option<f64>,
+    /// Shorter excursions are absorbed by the surrounding phase.
+    #[serde(default = "default_min_duration_ms")]
+    pub min_duration_ms: i64,
+    /// Moving-average window applied to the driver before classification; 0 disables it.
+    #[serde(default = "default_smoothing_ms")]
+    pub smoothing_ms: i64,
+    /// How much of the quiet phase after a full-load phase counts as cooldown.
+    #[serde(default = "default_cooldown_ms")]
+    pub cooldown_ms: i64,
+    /// Additional sensors summarized per phase.
+    #[serde(default)]
+    pub stats_sensors: Vec<String>,
+}
+
+impl Default for PhaseQuery {
+    fn default() -> Self {
+        Self {
+            sensor: None,
+            start: None,
+            end: None,
+            idle_below: None,
+            load_above: None,
+            min_duration_ms: default_min_duration_ms(),
+            smoothing_ms: default_smoothing_ms(),
+            cooldown_ms: default_cooldown_ms(),
+            stats_sensors: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PhaseKind {
+    Idle,
+    Light,
+    FullLoad,
+    /// Quiet period right after a full-load phase.
+    Cooldown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseThresholds {
+    pub idle_below: f64,
+    pub load_above: f64,
+}
+
+impl PhaseThresholds {
+    pub fn classify(&self, value: f64) -> PhaseKind {
+        if value < self.idle_below {
+            PhaseKind::Idle
+        } else if value >= self.load_above {
+            PhaseKind::FullLoad
+        } else {
+            PhaseKind::Light
+        }
+    }
+}
+
+/// Samples `first..=last` of a series classified as `kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseSpan {
+    pub kind: PhaseKind,
+    pub first: usize,
+    pub last: usize,
+}
+
+/// One phase; `end` is the start of the next phase (the last sample for the final one).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Phase {
+    pub kind: PhaseKind,
+    pub start: i64,
+    pub end: i64,
+    pub duration_ms: i64,
+    /// The driver sensor first, then the requested sensors.
+    pub stats: Vec<SensorStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseReport {
+    pub sensor: String,
+    pub idle_below: f64,
+    pub load_above: f64,
+    pub phases: Vec<Phase>,
+}
+
+/// Thresholds derived from the driver's unit and value range.
+pub fn default_thresholds(sensor: &str, values: &[f64]) -> PhaseThresholds {
+    if parse_unit(sensor).as_deref() == Some("%") {
+        return PhaseThresholds {
+            idle_below: 10.0,
+            load_above: 80.0,
+        };
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let low = percentile(&sorted, 1.0).unwrap_or(0.0);
+    let high = percentile(&sorted, 99.0).unwrap_or(0.0);
+    PhaseThresholds {
+        idle_below: low + (high - low) * 0.2,
+        load_above: low + (high - low) * 0.6,
+    }
+}
+
+/// Split a series into phases: classify every (smoothed) sample, absorb runs shorter than
+/// `min_duration_ms` into their longer neighbour and mark the first `cooldown_ms` of a quiet
+/// phase after full load as cooldown.
+pub fn segment(
+    timestamps: &[i64],
+    levels: &[Option<f64>],
+    thresholds: &PhaseThresholds,
+    min_duration_ms: i64,
+    cooldown_ms: i64,
+) -> Vec<PhaseSpan> {
+    // Missing samples continue the previous phase (or start with the first known one).
+    let first_known = levels.iter().flatten().next().map(|v| thresholds.classify(*v));
+    let Some(mut current) = first_known else {
+        return Vec::new();
+    };
+    let mut spans: Vec<PhaseSpan> = Vec::new();
+    for (i, level) in levels.iter().enumerate() {
+        if let Some(v) = level {
+            current = thresholds.classify(*v);
+        }
+        match spans.last_mut() {
+            Some(span) if span.kind == current => span.last = i,
+            _ => spans.push(PhaseSpan {
+                kind: current,
+                first: i,
+                last: i,
+            }),
+        }
+    }
+
+    let mut spans = absorb_short_spans(spans, timestamps, min_duration_ms);
+
+    if cooldown_ms > 0 {
+        let mut with_cooldown = Vec::with_capacity(spans.len());
+        for (i, span) in spans.iter().enumerate() {
+            let after_load = i > 0 && spans[i - 1].kind == PhaseKind::FullLoad;
+            if !after_load || span.kind == PhaseKind::FullLoad {
+                with_cooldown.push(*span);
+                continue;
+            }
+            let until = timestamps[span.first] + cooldown_ms;
+            let split = (span.first..=span.last).find(|&j| timestamps[j] >= until);
+            match split {
+                Some(j) if j > span.first => {
+                    with_cooldown.push(PhaseSpan {
+                        kind: PhaseKind::Cooldown,
+                        first: span.first,
+                        last: j - 1,
+                    });
+                    with_cooldown.push(PhaseSpan { first: j, ..*span });
+                }
+                _ => with_cooldown.push(PhaseSpan {
+                    kind: PhaseKind::Cooldown,
+                    ..*span
+                }),
+            }
+        }
+        spans = with_cooldown;
+    }
+    spans
+}
+
+/// Repeatedly absorb the shortest span under `min_duration_ms` into its longer neighbour,
+/// joining neighbours that end up of the same kind. Spans are kept as a linked list and
+/// picked from a min-heap whose stale entries are skipped, so this runs in O(n log n).
+fn absorb_short_spans(mut spans: Vec<PhaseSpan>, timestamps: &[i64], min_duration_ms: i64) -> Vec<PhaseSpan> {
+    let n = spans.len();
+    let mut prev: Vec<Option<usize>> = (0..n).map(|i| i.checked_sub(1)).collect();
+    let mut next: Vec<Option<usize>> = (0..n).map(|i| (i + 1 < n).then_some(i + 1)).collect();
+    let mut alive = vec![true; n];
+    let mut remaining = n;
+
+    let duration = |spans: &[PhaseSpan], next: &[Option<usize>], i: usize| {
+        let end = match next[i] {
+            Some(j) => timestamps[spans[j].first],
+            None => timestamps[spans[i].last],
+        };
+        end - timestamps[spans[i].first]
+    };
+    // Ties go to the earliest span; list order never changes, so the index keeps that order.
+    let mut heap: BinaryHeap<Reverse<(i64, usize)>> = (0..n)
+        .map(|i| (duration(&spans, &next, i), i))
+        .filter(|(d, _)| *d < min_duration_ms)
+        .map(Reverse)
+        .collect();
+
+    while remaining > 1 {
+        let Some(Reverse((d, i))) = heap.pop() else {
+            break;
+        };
+        if !alive[i] || duration(&spans, &next, i) != d {
+            continue;
+        }
+        let (p, q) = (prev[i], next[i]);
+        let prev_duration = p.map(|p| duration(&spans, &next, p));
+        let next_duration = q.map(|q| duration(&spans, &next, q));
+        if prev_duration.unwrap_or(-1) >= next_duration.unwrap_or(-1) {
+            // `p` exists here: a lone span never reaches this point.
+            spans[p.expect("previous span")].last = spans[i].last;
+        } else {
+            spans[q.expect("next span")].first = spans[i].first;
+        }
+        alive[i] = false;
+        remaining -= 1;
+        if let Some(p) = p {
+            next[p] = q;
+        }
+        if let Some(q) = q {
+            prev[q] = p;
+        }
+
+        // The neighbours of the absorbed run are now adjacent and may be of the same kind.
+        if let (Some(p), Some(q)) = (p, q) {
+            if spans[p].kind == spans[q].kind {
+                spans[p].last = spans[q].last;
+                alive[q] = false;
+                remaining -= 1;
+                next[p] = next[q];
+                if let Some(r) = next[q] {
+                    prev[r] = Some(p);
+                }
+            }
+        }
+
+        // Durations depend on the start of the following span, so the span before `p` changes too.
+        for j in [p.and_then(|p| prev[p]), p, q].into_iter().flatten() {
+            if alive[j] {
+                let d = duration(&spans, &next, j);
+                if d < min_duration_ms {
+                    heap.push(Reverse((d, j)));
+                }
+            }
+        }
+    }
+
+    spans.into_iter().zip(alive).filter_map(|(span, alive)| alive.then_some(span)).collect()
+}
+
+fn pick_driver(rows: &[DataRow]) -> Option<String> {
+    let first = rows.first()?;
+    let fields = sensor_fields(first);
+    DRIVER_CANDIDATES
+        .iter()
+        .find(|candidate| fields.iter().any(|(_, sensor)| sensor == *candidate))
+        .map(|s| s.to_string())
+}
+
+pub fn segment_phases(rows: &[DataRow], query: &PhaseQuery) -> Result<PhaseReport, String> {
+    let sensor = match &query.sensor {
+        Some(sensor) => sensor.clone(),
+        None => pick_driver(rows).ok_or_else(|| "未找到可用于分段的负载传感器，请手动指定".to_string())?,
+    };
+    let mut sensors = vec![sensor.clone()];
+    for extra in &query.stats_sensors {
+        if !sensors.contains(extra) {
+            sensors.push(extra.clone());
+        }
+    }
+
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors,
+            start: query.start,
+            end: query.end,
+            ..Default::default()
+        },
+    );
+    let driver = &set.series[0].values;
+    let known: Vec<f64> = driver.iter().flatten().copied().collect();
+    if known.is_empty() {
+        return Err(format!("传感器 {sensor} 没有可用的数值"));
+    }
+
+    let defaults = default_thresholds(&sensor, &known);
+    let thresholds = PhaseThresholds {
+        idle_below: query.idle_below.unwrap_or(defaults.idle_below),
+        load_above: query.load_above.unwrap_or(defaults.load_above),
+    };
+    if thresholds.idle_below > thresholds.load_above {
+        return Err("空闲阈值不能大于满载阈值".to_string());
+    }
+
+    let levels = if query.smoothing_ms > 0 {
+        smooth(
+            &set.timestamps,
+            driver,
+            &Smoothing::Sma {
+                window_ms: query.smoothing_ms,
+            },
+        )
+    } else {
+        driver.clone()
+    };
+    let spans = segment(
+        &set.timestamps,
+        &levels,
+        &thresholds,
+        query.min_duration_ms,
+        query.cooldown_ms,
+    );
+
+    let phases = spans
+        .iter()
+        .enumerate()
+        .map(|(i, span)| {
+            let start = set.timestamps[span.first];
+            let end = spans
+                .get(i + 1)
+                .map(|next| set.timestamps[next.first])
+                .unwrap_or(set.timestamps[span.last]);
+            let range = span.first..span.last + 1;
+            Phase {
+                kind: span.kind,
+                start,
+                end,
+                duration_ms: end - start,
+                stats: set
+                    .series
+                    .iter()
+                    .map(|s| describe(&s.sensor, &set.timestamps[range.clone()], &s.values[range.clone()], &[]))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(PhaseReport {
+        sensor,
+        idle_below: thresholds.idle_below,
+        load_above: thresholds.load_above,
+        phases,
+    })
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+/// Toneless pinyin (`ü` written as `v`) for the characters used in HWiNFO's Chinese sensor,
+/// device and unit names, plus common hardware vocabulary. Polyphonic characters use the
+/// reading found in hardware terms (率 → lv as in 频率, 调 → tiao as in 调节).
+const TABLE: &str = "
+an 安按
+ba 八
+bai 百白
+ban 板半版
+bao 报保包
+bei 备倍被北背
+ben 本
+beng 泵
+bi 比必闭
+bian 变边编
+biao 标表
+bie 别
+bing 并病
+bo 波
+bu 不部步布
+cai 采才
+can 参残
+cao 操
+ce 测侧策
+cha 差插查
+chang 长常场
+chao 超
+che 车
+cheng 程成承称
+chi 持池尺
+chong 重冲充
+chu 出处储初
+chuan 传串
+chuang 窗创
+ci 次磁此
+cun 存
+cuo 错
+da 大达打
+dai 带待代
+dan 单弹
+dang 当档
+dao 到导
+de 的得
+deng 等
+di 低地第底递
+dian 电点
+ding 定顶
+dong 动
+du 度读独
+duan 端断段
+dui 对队
+duo 多
+e 额
+er 二
+fa 发阀
+fan 反范返
+fang 放方访
+fei 非费
+fen 分份
+feng 风峰封
+fou 否
+fu 负复浮副服符
+gai 改
+gan 感
+gao 高告
+ge 个格各
+gen 根
+gong 功共供工
+gu 故固估
+gua 挂
+guan 关管
+gui 规
+guo 过国裹
+han 含
+hao 耗号
+he 核和合盒
+hou 后
+hu 户互
+hua 化画
+huan 缓换环
+hui 回会
+huo 活或获
+ji 机计级基积集击即寄极记际
+jia 加架假
+jian 间检键件建
+jiang 降
+jiao 交校较
+jie 接结节界
+jin 进近仅
+jing 警经精
+jiu 就
+ju 局据距
+jun 均
+ka 卡
+kai 开
+kao 靠
+ke 可壳客
+kong 控空
+kou 口
+ku 库
+kuai 块快
+kuan 宽
+lei 类累
+leng 冷
+li 理力例立历
+lian 链连
+liang 量两
+lie 列
+lin 临
+liu 流留
+lu 路录
+lv 率滤
+luo 逻络
+ma 码
+man 满
+mei 每
+men 门
+mi 密
+mian 面
+miao 秒
+ming 命名
+mo 模末
+mu 目
+nei 内
+neng 能
+ni 拟
+pai 排派
+pan 盘
+pei 配
+pian 偏片
+pin 频
+ping 平屏
+qi 器其启期气
+qian 前
+qiang 强
+qiao 桥
+qie 切
+qing 请清擎
+qu 取区驱
+quan 权全
+que 确缺
+re 热
+ren 任
+ri 日
+rong 容
+ru 入
+ruan 软
+sao 扫
+shan 扇闪
+shang 上
+she 设
+shen 深
+sheng 剩升省生
+shi 时使实示式事识失始十视是
+shou 受寿收
+shu 数输属
+shuang 双
+shui 水
+si 死四
+su 速
+suan 算
+suo 锁
+tai 态
+ti 提体
+tiao 调
+tong 通统
+tou 头
+tu 图
+wai 外
+wan 完
+wang 网
+wei 位未为维
+wen 温文
+wu 物误无务
+xi 系息
+xia 下
+xian 显线限现
+xiang 享项相
+xiao 效小消
+xie 写
+xin 心芯信
+xing 性行型
+xu 虚需序
+xuan 选
+ya 压
+yan 延验
+ye 页
+yi 已一异意以
+yin 引因
+ying 硬应影
+yong 用
+you 有优
+yu 余预于
+yuan 源远
+yun 运
+zai 载在
+zao 噪
+zeng 增
+zhan 占站
+zhang 障
+zhen 帧真
+zheng 正整
+zhi 值制指置直只
+zhong 中
+zhou 周轴
+zhu 主驻
+zhuan 专转
+zhuang 装状
+zhun 准
+zi 字自
+zong 总
+zu 组
+zui 最
+zuo 作
+";
+
+lazy_static! {
+    static ref PINYIN: HashMap<char, &'static str> = TABLE
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .flat_map(|(syllable, chars)| chars.chars().map(move |c| (c, syllable)))
+        .collect();
+}
+
+/// CJK unified ideographs, including extension A and the compatibility block.
+pub fn is_hanzi(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+pub fn syllable(c: char) -> Option<&'static str> {
+    PINYIN.get(&c).copied()
+}
+
+/// Romanized forms of a name used for matching: full pinyin and initial letters.
+///
+/// ASCII letters and digits are kept (lowercased) in both forms, other symbols are dropped,
+/// e.g. `CPU 核心频率` → (`cpuhexinpinlv`, `cpuhxpl`). A character missing from the table
+/// becomes a space, so the rest of the name still matches but no match spans the gap
+/// (`核龘心` → (`he xin`, `h x`)). Returns `None` when the name has no known Chinese characters.
+pub fn romanize(name: &str) -> Option<(String, String)> {
+    let mut full = String::new();
+    let mut initials = String::new();
+    let mut has_hanzi = false;
+    for c in name.chars() {
+        if let Some(s) = syllable(c) {
+            has_hanzi = true;
+            full.push_str(s);
+            initials.push_str(&s[..1]);
+        } else if is_hanzi(c) {
+            if !full.is_empty() && !full.ends_with(' ') {
+                full.push(' ');
+                initials.push(' ');
+            }
+        } else if c.is_ascii_alphanumeric() {
+            let c = c.to_ascii_lowercase();
+            full.push(c);
+            initials.push(c);
+        }
+    }
+    has_hanzi.then(|| (full.trim_end().to_string(), initials.trim_end().to_string()))
+}
@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::row_timestamp;
+use crate::models::{DataGroup, DataRow};
+
+/// Row query over a dataset: time window, sensor selection and pagination.
+///
+/// Every part is optional; an empty query returns all rows.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowQuery {
+    /// Inclusive window start, unix ms (the injected `Timestamp`).
+    pub start: Option<i64>,
+    /// Inclusive window end, unix ms.
+    pub end: Option<i64>,
+    /// Field names to keep besides the `base` group; `None` keeps every sensor.
+    pub sensors: Option<Vec<String>>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowPage {
+    /// Number of rows inside the time window (before pagination).
+    pub total: usize,
+    pub offset: usize,
+    pub rows: Vec<DataRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeBounds {
+    pub start: i64,
+    pub end: i64,
+    pub row_count: usize,
+}
+
+impl RowQuery {
+    fn has_window(&self) -> bool {
+        self.start.is_some() || self.end.is_some()
+    }
+
+    fn contains(&self, ts: i64) -> bool {
+        self.start.map(|s| ts >= s).unwrap_or(true) && self.end.map(|e| ts <= e).unwrap_or(true)
+    }
+
+    /// Whether a row falls inside the time window. Rows without a timestamp only match
+    /// when no window is given.
+    pub fn matches(&self, row: &DataRow) -> bool {
+        if !self.has_window() {
+            return true;
+        }
+        row_timestamp(row).map(|ts| self.contains(ts)).unwrap_or(false)
+    }
+}
+
+/// First and last timestamp of a dataset, so the UI can set up its axis without row data.
+pub fn time_bounds(rows: &[DataRow]) -> Option<TimeBounds> {
+    let mut timestamps = rows.iter().filter_map(row_timestamp);
+    let first = timestamps.next()?;
+    let (start, end) = timestamps.fold((first, first), |(lo, hi), ts| (lo.min(ts), hi.max(ts)));
+    Some(TimeBounds {
+        start,
+        end,
+        row_count: rows.len(),
+    })
+}
+
+pub fn query_rows(rows: &[DataRow], query: &RowQuery) -> RowPage {
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(usize::MAX);
+    let sensors: Option<HashSet<&str>> = query
+        .sensors
+        .as_ref()
+        .map(|s| s.iter().map(|name| name.as_str()).collect());
+
+    let mut total = 0usize;
+    let mut page = Vec::new();
+    for row in rows.iter().filter(|row| query.matches(row)) {
+        if total >= offset && page.len() < limit {
+            page.push(match &sensors {
+                Some(sensors) => select_sensors(row, sensors),
+                None => row.clone(),
+            });
+        }
+        total += 1;
+    }
+
+    RowPage {
+        total,
+        offset,
+        rows: page,
+    }
+}
+
+/// Keep the `base` group plus the selected fields, dropping groups that end up empty.
+fn select_sensors(row: &DataRow, sensors: &HashSet<&str>) -> DataRow {
+    row.iter()
+        .filter_map(|(name, group)| {
+            let group = if name == "base" {
+                group.clone()
+            } else {
+                select_in_group(group, sensors)?
+            };
+            Some((name.clone(), group))
+        })
+        .collect()
+}
+
+fn select_in_group(group: &DataGroup, sensors: &HashSet<&str>) -> Option<DataGroup> {
+    let mut selected = DataGroup::new();
+    for (field, value) in &group.fields {
+        if sensors.contains(field.as_str()) {
+            selected.fields.insert(field.clone(), value.clone());
+        }
+    }
+    for (name, child) in &group.children {
+        if let Some(child) = select_in_group(child, sensors) {
+            selected.children.insert(name.clone(), child);
+        }
+    }
+
+    if selected.fields.is_empty() && selected.children.is_empty() {
+        None
+    } else {
+        Some(selected)
+    }
+}
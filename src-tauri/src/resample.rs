@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::series::{sample_step, SeriesSet};
+
+/// Finest grid accepted from the frontend.
+const MIN_INTERVAL_MS: i64 = 10;
+/// Default gap threshold, in grid intervals (or native sample intervals if those are longer).
+const DEFAULT_GAP_STEPS: i64 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResampleMethod {
+    /// Value of the closest sample.
+    Nearest,
+    /// Linear interpolation between the samples around the grid point.
+    #[default]
+    Linear,
+    /// Last sample at or before the grid point (sample-and-hold).
+    Previous,
+    /// Mean of the samples in `[t, t + interval)`.
+    Mean,
+}
+
+/// What to put on grid points inside a gap (samples further apart than `max_gap_ms`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GapFill {
+    /// Leave the points empty so charts break and statistics skip them.
+    #[default]
+    Empty,
+    /// Hold the last value before the gap.
+    Previous,
+    /// Interpolate across the gap.
+    Linear,
+    Zero,
+}
+
+/// Fixed-interval grid used by series export, statistics and comparisons.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResampleSpec {
+    pub interval_ms: i64,
+    #[serde(default)]
+    pub method: ResampleMethod,
+    /// Two samples further apart than this enclose a gap. Defaults to three grid intervals
+    /// (or three native sample intervals, if the log is sampled more coarsely than the grid).
+    pub max_gap_ms: Option<i64>,
+    #[serde(default)]
+    pub fill: GapFill,
+}
+
+impl ResampleSpec {
+    pub fn new(interval_ms: i64, method: ResampleMethod) -> Self {
+        Self {
+            interval_ms,
+            method,
+            max_gap_ms: None,
+            fill: GapFill::Empty,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_ms < MIN_INTERVAL_MS {
+            return Err(format!("重采样间隔不能小于 {MIN_INTERVAL_MS} ms"));
+        }
+        if self.max_gap_ms.is_some_and(|gap| gap < 0) {
+            return Err("最大间隔不能为负数".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Grid from `origin` (or the first timestamp) to the last timestamp.
+pub fn grid(timestamps: &[i64], interval_ms: i64, origin: Option<i64>) -> Vec<i64> {
+    let (Some(&first), Some(&last)) = (timestamps.first(), timestamps.last()) else {
+        return Vec::new();
+    };
+    if interval_ms <= 0 {
+        return Vec::new();
+    }
+    let origin = origin.unwrap_or(first);
+    // First grid point at or after the first sample.
+    let mut t = if origin >= first {
+        origin
+    } else {
+        origin + (first - origin + interval_ms - 1) / interval_ms * interval_ms
+    };
+    let mut points = Vec::new();
+    while t <= last {
+        points.push(t);
+        t += interval_ms;
+    }
+    points
+}
+
+/// Resample one series onto `grid`. Grid points before the first or after the last valid
+/// sample stay empty.
+pub fn resample_values(
+    timestamps: &[i64],
+    values: &[Option<f64>],
+    grid: &[i64],
+    spec: &ResampleSpec,
+    max_gap_ms: i64,
+) -> Vec<Option<f64>> {
+    let samples: Vec<(i64, f64)> = timestamps
+        .iter()
+        .zip(values)
+        .filter_map(|(&ts, v)| v.map(|v| (ts, v)))
+        .collect();
+    let lerp = |(t0, v0): (i64, f64), (t1, v1): (i64, f64), t: i64| {
+        if t1 == t0 {
+            v0
+        } else {
+            v0 + (v1 - v0) * (t - t0) as f64 / (t1 - t0) as f64
+        }
+    };
+
+    // `next` is the index of the first sample after the grid point.
+    let mut next = 0usize;
+    grid.iter()
+        .map(|&t| {
+            let bucket_start = next;
+            while next < samples.len() && samples[next].0 <= t {
+                next += 1;
+            }
+            if spec.method == ResampleMethod::Mean {
+                let from = bucket_start + samples[bucket_start..next].partition_point(|(ts, _)| *ts < t);
+                let bucket: Vec<f64> = samples[from..]
+                    .iter()
+                    .take_while(|(ts, _)| *ts < t + spec.interval_ms)
+                    .map(|(_, v)| *v)
+                    .collect();
+                if !bucket.is_empty() {
+                    return Some(bucket.iter().sum::<f64>() / bucket.len() as f64);
+                }
+            }
+
+            let prev = next.checked_sub(1).map(|i| samples[i])?;
+            if prev.0 == t {
+                return Some(prev.1);
+            }
+            let after = samples.get(next).copied()?;
+            if after.0 - prev.0 > max_gap_ms {
+                return match spec.fill {
+                    GapFill::Empty => None,
+                    GapFill::Previous => Some(prev.1),
+                    GapFill::Linear => Some(lerp(prev, after, t)),
+                    GapFill::Zero => Some(0.0),
+                };
+            }
+
+            Some(match spec.method {
+                ResampleMethod::Nearest if after.0 - t < t - prev.0 => after.1,
+                ResampleMethod::Nearest | ResampleMethod::Previous => prev.1,
+                // An empty bucket means the grid is finer than the log: interpolate.
+                ResampleMethod::Linear | ResampleMethod::Mean => lerp(prev, after, t),
+            })
+        })
+        .collect()
+}
+
+/// Replace the samples of `set` with values on a fixed grid starting at `origin` (or the
+/// first sample). `source_count` keeps the number of original samples.
+pub fn resample_set(set: &mut SeriesSet, spec: &ResampleSpec, origin: Option<i64>) {
+    if spec.interval_ms <= 0 {
+        return;
+    }
+    let grid = grid(&set.timestamps, spec.interval_ms, origin);
+    let max_gap_ms = spec
+        .max_gap_ms
+        .unwrap_or_else(|| DEFAULT_GAP_STEPS * spec.interval_ms.max(sample_step(&set.timestamps)));
+    for series in &mut set.series {
+        series.values = resample_values(&set.timestamps, &series.values, &grid, spec, max_gap_ms);
+    }
+    set.timestamps = grid;
+}
@@ -0,0 +1,173 @@
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::pinyin::romanize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchMode {
+    /// Case-insensitive substring, also against the pinyin of Chinese names.
+    Substring,
+    Regex,
+    /// Substring matches first, then ordered-subsequence matches on names and pinyin.
+    #[default]
+    Fuzzy,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub query: String,
+    #[serde(default)]
+    pub mode: SearchMode,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+    Pinyin,
+    Regex,
+    Fuzzy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub sensor: String,
+    /// Group path of the sensor, top-level group first.
+    pub path: Vec<String>,
+    pub score: i64,
+    pub matched_by: MatchKind,
+}
+
+/// Fuzzy score of `pattern` as an ordered subsequence of `text` (both lowercased), or `None`.
+///
+/// Consecutive matches and matches at word starts score higher, skipped characters cost a
+/// little, so `cpuclk` ranks `CPU Clock` above `CPU Core Load`.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0usize;
+    let mut prev_match: Option<usize> = None;
+
+    for p in pattern.chars().filter(|c| !c.is_whitespace()) {
+        let offset = text[pos..].iter().position(|&c| c == p)?;
+        let i = pos + offset;
+
+        score += 16;
+        if prev_match.is_some_and(|prev| prev + 1 == i) {
+            score += 12;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        // Skipped characters cost a little, long skips do not drown the score.
+        score -= (offset as i64).min(8);
+
+        prev_match = Some(i);
+        pos = i + 1;
+    }
+    Some(score)
+}
+
+/// Score one name in substring/fuzzy mode; higher is better.
+fn score_name(query: &str, name: &str, fuzzy: bool) -> Option<(i64, MatchKind)> {
+    let lower = name.to_lowercase();
+    let length_penalty = (lower.chars().count() as i64).min(100);
+
+    if lower == query {
+        return Some((10_000, MatchKind::Exact));
+    }
+    if lower.starts_with(query) {
+        return Some((9_000 - length_penalty, MatchKind::Prefix));
+    }
+    if let Some(at) = lower.find(query) {
+        let at = lower[..at].chars().count() as i64;
+        return Some((8_000 - at.min(100) * 4 - length_penalty, MatchKind::Substring));
+    }
+
+    let compact: String = query.chars().filter(|c| !c.is_whitespace()).collect();
+    let romanized = romanize(name);
+    if let Some((full, initials)) = &romanized {
+        if initials.starts_with(&compact) || full.starts_with(&compact) {
+            return Some((7_000 - length_penalty, MatchKind::Pinyin));
+        }
+        if initials.contains(&compact) || full.contains(&compact) {
+            return Some((6_500 - length_penalty, MatchKind::Pinyin));
+        }
+    }
+
+    if !fuzzy {
+        return None;
+    }
+    let mut best = fuzzy_score(query, &lower).map(|s| (s, MatchKind::Fuzzy));
+    if let Some((full, initials)) = &romanized {
+        for text in [full, initials] {
+            if let Some(s) = fuzzy_score(&compact, text) {
+                // Pinyin subsequences are weaker evidence than matches on the name itself.
+                let s = s - 8;
+                if best.is_none_or(|(b, _)| s > b) {
+                    best = Some((s, MatchKind::Pinyin));
+                }
+            }
+        }
+    }
+    best.map(|(s, kind)| (s - length_penalty, kind))
+}
+
+/// Rank `sensors` (group path, field name) against the query. Best matches come first; ties
+/// are broken by name so results are stable.
+pub fn search_sensors(
+    sensors: &[(Vec<String>, String)],
+    query: &SearchQuery,
+) -> Result<Vec<SearchHit>, regex::Error> {
+    let needle = query.query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut hits: Vec<SearchHit> = match query.mode {
+        SearchMode::Regex => {
+            let re = RegexBuilder::new(query.query.trim()).case_insensitive(true).build()?;
+            sensors
+                .iter()
+                .filter_map(|(path, name)| {
+                    let m = re.find(name)?;
+                    Some(SearchHit {
+                        sensor: name.clone(),
+                        path: path.clone(),
+                        score: 8_000 - (m.start() as i64).min(100) * 4 - (name.len() as i64).min(100),
+                        matched_by: MatchKind::Regex,
+                    })
+                })
+                .collect()
+        }
+        mode => sensors
+            .iter()
+            .filter_map(|(path, name)| {
+                let (score, matched_by) = score_name(&needle, name, mode == SearchMode::Fuzzy)?;
+                Some(SearchHit {
+                    sensor: name.clone(),
+                    path: path.clone(),
+                    score,
+                    matched_by,
+                })
+            })
+            .collect(),
+    };
+
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.sensor.cmp(&b.sensor))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    if let Some(limit) = query.limit {
+        hits.truncate(limit);
+    }
+    Ok(hits)
+}
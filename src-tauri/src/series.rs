@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::row_timestamp;
+use crate::downsample::{downsample_indices, Downsample};
+use crate::models::{DataGroup, DataRow};
+use crate::resample::{resample_set, ResampleSpec};
+use crate::smoothing::{smooth, Smoothing};
+
+/// Sensors to extract as time series, optionally limited to a time window (unix ms, inclusive).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesQuery {
+    pub sensors: Vec<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Target number of points per series, usually derived from the chart width.
+    pub max_points: Option<usize>,
+    #[serde(default)]
+    pub downsample: Downsample,
+    /// Put the samples on a fixed grid (aligned to `start` when given) before downsampling.
+    #[serde(default)]
+    pub resample: Option<ResampleSpec>,
+    /// Smoothing per entry of `sensors` (by position); missing entries return raw values. The
+    /// same sensor may be listed twice to get the raw and the smoothed line in one request.
+    #[serde(default)]
+    pub smoothing: Vec<Option<Smoothing>>,
+    /// Group path per entry of `sensors` (by position), for a name logged by several
+    /// devices; missing entries read the first group holding the name.
+    #[serde(default)]
+    pub paths: Vec<Option<Vec<String>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorSeries {
+    pub sensor: String,
+    /// One value per entry of [`SeriesSet::timestamps`]; `None` where the sample is missing or not numeric.
+    pub values: Vec<Option<f64>>,
+    pub smoothing: Option<Smoothing>,
+}
+
+/// Parallel arrays: `series[i].values[j]` was sampled at `timestamps[j]`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesSet {
+    pub timestamps: Vec<i64>,
+    pub series: Vec<SensorSeries>,
+    /// Rows dropped because they carry no usable timestamp.
+    pub skipped_rows: usize,
+    /// Number of logged samples in the window, before resampling and downsampling.
+    pub source_count: usize,
+}
+
+impl SeriesSet {
+    /// Render the series as CSV: a time column followed by one column per sensor.
+    pub fn to_csv(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        let mut header = vec!["Time".to_string()];
+        header.extend(self.series.iter().map(|s| s.sensor.clone()));
+        writer.write_record(&header)?;
+
+        for (i, ts) in self.timestamps.iter().enumerate() {
+            let time = chrono::DateTime::from_timestamp_millis(*ts)
+                .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+                .unwrap_or_default();
+            let mut record = vec![time];
+            record.extend(
+                self.series
+                    .iter()
+                    .map(|s| s.values.get(i).copied().flatten().map(|v| v.to_string()).unwrap_or_default()),
+            );
+            writer.write_record(&record)?;
+        }
+
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+/// Parse a raw CSV cell as a sample value. `[Yes/No]` flags become 1 and 0.
+pub fn parse_value(raw: &str) -> Option<f64> {
+    let raw = raw.trim();
+    match raw.parse::<f64>() {
+        Ok(v) => Some(v).filter(|v| v.is_finite()),
+        Err(_) => parse_bool(raw).map(|b| if b { 1.0 } else { 0.0 }),
+    }
+}
+
+/// Parse an HWiNFO `[Yes/No]` cell, in English or as the localized `是`/`否`.
+pub fn parse_bool(raw: &str) -> Option<bool> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("yes") || raw == "是" {
+        Some(true)
+    } else if raw.eq_ignore_ascii_case("no") || raw == "否" {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Median spacing (ms) of ascending timestamps; 0 with fewer than two distinct samples.
+pub fn sample_step(timestamps: &[i64]) -> i64 {
+    let mut steps: Vec<i64> = timestamps.windows(2).map(|w| w[1] - w[0]).filter(|d| *d > 0).collect();
+    if steps.is_empty() {
+        return 0;
+    }
+    steps.sort_unstable();
+    steps[steps.len() / 2]
+}
+
+/// Group path (top-level group first) of the first group holding `field`.
+///
+/// Group names are visited in sorted order so that a field name shared by several
+/// devices always resolves to the same one.
+pub fn find_field_path(row: &DataRow, field: &str) -> Option<Vec<String>> {
+    fn search(groups: &HashMap<String, DataGroup>, field: &str, path: &mut Vec<String>) -> bool {
+        let mut names: Vec<&String> = groups.keys().collect();
+        names.sort();
+        for name in names {
+            let group = &groups[name];
+            path.push(name.clone());
+            if group.fields.contains_key(field) || search(&group.children, field, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    let mut path = Vec::new();
+    search(row, field, &mut path).then_some(path)
+}
+
+/// Every sensor field of a row as `(group path, field name)`, sorted by path and name.
+///
+/// The `base` group (Date, Time, Timestamp) is not a sensor and is skipped.
+pub fn sensor_fields(row: &DataRow) -> Vec<(Vec<String>, String)> {
+    fn walk(groups: &HashMap<String, DataGroup>, path: &mut Vec<String>, out: &mut Vec<(Vec<String>, String)>) {
+        for (name, group) in groups {
+            path.push(name.clone());
+            out.extend(group.fields.keys().map(|field| (path.clone(), field.clone())));
+            walk(&group.children, path, out);
+            path.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut path = Vec::new();
+    for (name, group) in row.iter().filter(|(name, _)| name.as_str() != "base") {
+        path.push(name.clone());
+        out.extend(group.fields.keys().map(|field| (path.clone(), field.clone())));
+        walk(&group.children, &mut path, &mut out);
+        path.pop();
+    }
+    out.sort();
+    out
+}
+
+/// Raw value of `field` in the group at `path`.
+pub fn field_at<'a>(row: &'a DataRow, path: &[String], field: &str) -> Option<&'a str> {
+    let (first, rest) = path.split_first()?;
+    let mut group = row.get(first)?;
+    for name in rest {
+        group = group.children.get(name)?;
+    }
+    group.fields.get(field).map(String::as_str)
+}
+
+/// Resolves a sensor once and then reads it from every row by path.
+pub(crate) struct FieldLocator<'a> {
+    field: &'a str,
+    path: Option<Vec<String>>,
+    /// The path was given by the caller and is never re-resolved by name.
+    pinned: bool,
+}
+
+impl<'a> FieldLocator<'a> {
+    pub(crate) fn new(field: &'a str) -> Self {
+        Self {
+            field,
+            path: None,
+            pinned: false,
+        }
+    }
+
+    pub(crate) fn at(field: &'a str, path: Vec<String>) -> Self {
+        Self {
+            field,
+            path: Some(path),
+            pinned: true,
+        }
+    }
+
+    pub(crate) fn get<'r>(&mut self, row: &'r DataRow) -> Option<&'r str> {
+        if let Some(value) = self.path.as_ref().and_then(|p| field_at(row, p, self.field)) {
+            return Some(value);
+        }
+        if self.pinned {
+            return None;
+        }
+        // Not resolved yet, or this row is laid out differently (e.g. after a reload).
+        let path = find_field_path(row, self.field)?;
+        let value = field_at(row, &path, self.field);
+        self.path = Some(path);
+        value
+    }
+}
+
+pub fn extract_series(rows: &[DataRow], query: &SeriesQuery) -> SeriesSet {
+    let mut locators: Vec<FieldLocator> = query
+        .sensors
+        .iter()
+        .enumerate()
+        .map(|(i, sensor)| match query.paths.get(i).cloned().flatten() {
+            Some(path) => FieldLocator::at(sensor, path),
+            None => FieldLocator::new(sensor),
+        })
+        .collect();
+    let mut set = SeriesSet {
+        series: query
+            .sensors
+            .iter()
+            .enumerate()
+            .map(|(i, sensor)| SensorSeries {
+                sensor: sensor.clone(),
+                values: Vec::new(),
+                smoothing: query.smoothing.get(i).copied().flatten(),
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    for row in rows {
+        let Some(ts) = row_timestamp(row) else {
+            set.skipped_rows += 1;
+            continue;
+        };
+        if query.start.is_some_and(|s| ts < s) || query.end.is_some_and(|e| ts > e) {
+            continue;
+        }
+
+        set.timestamps.push(ts);
+        for (series, locator) in set.series.iter_mut().zip(locators.iter_mut()) {
+            series.values.push(locator.get(row).and_then(parse_value));
+        }
+    }
+
+    set.source_count = set.timestamps.len();
+    for series in &mut set.series {
+        if let Some(smoothing) = &series.smoothing {
+            series.values = smooth(&set.timestamps, &series.values, smoothing);
+        }
+    }
+    if let Some(spec) = &query.resample {
+        resample_set(&mut set, spec, query.start);
+    }
+    if let Some(max_points) = query.max_points {
+        downsample_set(&mut set, max_points, query.downsample);
+    }
+    set
+}
+
+/// Downsample every series and keep the union of the chosen samples, so the arrays stay
+/// parallel (each series still gets at least its own selection).
+fn downsample_set(set: &mut SeriesSet, max_points: usize, method: Downsample) {
+    if set.timestamps.len() <= max_points {
+        return;
+    }
+
+    let mut keep = vec![false; set.timestamps.len()];
+    for series in &set.series {
+        for i in downsample_indices(&set.timestamps, &series.values, max_points, method) {
+            keep[i] = true;
+        }
+    }
+
+    let pick = |values: &[Option<f64>]| -> Vec<Option<f64>> {
+        values.iter().zip(&keep).filter(|(_, k)| **k).map(|(v, _)| *v).collect()
+    };
+    for series in &mut set.series {
+        series.values = pick(&series.values);
+    }
+    set.timestamps = set
+        .timestamps
+        .iter()
+        .zip(&keep)
+        .filter(|(_, k)| **k)
+        .map(|(ts, _)| *ts)
+        .collect();
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+/// Smoothing filter applied to a series before resampling and downsampling. Windows and
+/// time constants are in milliseconds, so they mean the same thing at any polling interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Smoothing {
+    /// Mean of the samples within a window centred on each sample.
+    Sma { window_ms: i64 },
+    /// Exponential moving average; each step decays by `exp(-dt / tau)`, so irregular
+    /// sample spacing is accounted for.
+    Ema { tau_ms: i64 },
+    /// Median of the samples within a window centred on each sample; keeps steps sharp
+    /// while removing single-sample spikes.
+    Median { window_ms: i64 },
+}
+
+impl Smoothing {
+    pub fn validate(&self) -> Result<(), String> {
+        let span = match *self {
+            Smoothing::Sma { window_ms } | Smoothing::Median { window_ms } => window_ms,
+            Smoothing::Ema { tau_ms } => tau_ms,
+        };
+        if span <= 0 {
+            return Err("平滑窗口必须大于 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Smooth `values` sampled at ascending `timestamps`. Missing samples stay missing and are
+/// left out of the windows of their neighbours.
+pub fn smooth(timestamps: &[i64], values: &[Option<f64>], smoothing: &Smoothing) -> Vec<Option<f64>> {
+    match *smoothing {
+        Smoothing::Sma { window_ms } => centred_window(timestamps, values, window_ms, |window| {
+            window.iter().sum::<f64>() / window.len() as f64
+        }),
+        Smoothing::Median { window_ms } => centred_window(timestamps, values, window_ms, |window| {
+            let mut sorted = window.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let mid = sorted.len() / 2;
+            if sorted.len() % 2 == 0 {
+                (sorted[mid - 1] + sorted[mid]) / 2.0
+            } else {
+                sorted[mid]
+            }
+        }),
+        Smoothing::Ema { tau_ms } => ema(timestamps, values, tau_ms),
+    }
+}
+
+fn centred_window(
+    timestamps: &[i64],
+    values: &[Option<f64>],
+    window_ms: i64,
+    reduce: impl Fn(&[f64]) -> f64,
+) -> Vec<Option<f64>> {
+    let samples: Vec<(i64, f64)> = timestamps
+        .iter()
+        .zip(values)
+        .filter_map(|(&ts, v)| v.map(|v| (ts, v)))
+        .collect();
+    let half = window_ms / 2;
+
+    let (mut lo, mut hi) = (0usize, 0usize);
+    let mut window = Vec::new();
+    timestamps
+        .iter()
+        .zip(values)
+        .map(|(&ts, value)| {
+            (*value)?;
+            while lo < samples.len() && samples[lo].0 < ts - half {
+                lo += 1;
+            }
+            while hi < samples.len() && samples[hi].0 <= ts + half {
+                hi += 1;
+            }
+            window.clear();
+            window.extend(samples[lo..hi].iter().map(|(_, v)| *v));
+            Some(reduce(&window))
+        })
+        .collect()
+}
+
+fn ema(timestamps: &[i64], values: &[Option<f64>], tau_ms: i64) -> Vec<Option<f64>> {
+    let mut state: Option<(i64, f64)> = None;
+    timestamps
+        .iter()
+        .zip(values)
+        .map(|(&ts, value)| {
+            let v = (*value)?;
+            let smoothed = match state {
+                Some((prev_ts, prev)) => {
+                    let alpha = 1.0 - (-((ts - prev_ts).max(0) as f64) / tau_ms as f64).exp();
+                    prev + alpha * (v - prev)
+                }
+                None => v,
+            };
+            state = Some((ts, smoothed));
+            Some(smoothed)
+        })
+        .collect()
+}
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataRow;
+use crate::resample::ResampleSpec;
+use crate::series::{extract_series, SeriesQuery};
+
+fn default_percentiles() -> Vec<f64> {
+    vec![1.0, 5.0, 25.0, 75.0, 95.0, 99.0]
+}
+
+/// Statistics request: sensors, optional time window (unix ms, inclusive) and percentiles (0-100).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsQuery {
+    pub sensors: Vec<String>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    #[serde(default = "default_percentiles")]
+    pub percentiles: Vec<f64>,
+    /// Compute the statistics on a fixed-interval grid instead of the logged samples.
+    #[serde(default)]
+    pub resample: Option<ResampleSpec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Percentile {
+    pub p: f64,
+    pub value: f64,
+}
+
+/// Descriptive statistics of one sensor. Value fields are `None` when there are no samples.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorStats {
+    pub sensor: String,
+    pub count: usize,
+    pub missing: usize,
+    pub min: Option<f64>,
+    pub min_at: Option<i64>,
+    pub max: Option<f64>,
+    pub max_at: Option<i64>,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    /// Sample standard deviation (n - 1); 0 for a single sample.
+    pub stddev: Option<f64>,
+    pub percentiles: Vec<Percentile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsTable {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub percentiles: Vec<f64>,
+    pub rows: Vec<SensorStats>,
+}
+
+/// Percentile (0-100) of sorted values, linearly interpolated between closest ranks.
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    Some(sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64))
+}
+
+pub fn describe(sensor: &str, timestamps: &[i64], values: &[Option<f64>], percentiles: &[f64]) -> SensorStats {
+    let mut stats = SensorStats {
+        sensor: sensor.to_string(),
+        ..Default::default()
+    };
+
+    let mut samples = Vec::with_capacity(values.len());
+    for (&ts, value) in timestamps.iter().zip(values) {
+        let Some(v) = *value else {
+            stats.missing += 1;
+            continue;
+        };
+        if stats.min.is_none_or(|min| v < min) {
+            stats.min = Some(v);
+            stats.min_at = Some(ts);
+        }
+        if stats.max.is_none_or(|max| v > max) {
+            stats.max = Some(v);
+            stats.max_at = Some(ts);
+        }
+        samples.push(v);
+    }
+
+    stats.count = samples.len();
+    if samples.is_empty() {
+        return stats;
+    }
+
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = if samples.len() > 1 {
+        samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+    stats.mean = Some(mean);
+    stats.stddev = Some(variance.sqrt());
+
+    samples.sort_by(f64::total_cmp);
+    stats.median = percentile(&samples, 50.0);
+    stats.percentiles = percentiles
+        .iter()
+        .filter_map(|&p| percentile(&samples, p).map(|value| Percentile { p, value }))
+        .collect();
+    stats
+}
+
+/// Welch's unequal-variance t-test of `mean(b) - mean(a)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WelchTest {
+    pub t: f64,
+    /// Welch-Satterthwaite degrees of freedom.
+    pub df: f64,
+    /// Two-sided p-value.
+    pub p_value: f64,
+}
+
+/// Welch's t-test; `None` with fewer than two samples on a side or when both are constant.
+///
+/// Consecutive sensor samples are autocorrelated, so p-values are optimistic for long logs;
+/// they are best read together with the size of the difference.
+pub fn welch_t_test(a: &[f64], b: &[f64]) -> Option<WelchTest> {
+    fn mean_var(v: &[f64]) -> (f64, f64) {
+        let n = v.len() as f64;
+        let mean = v.iter().sum::<f64>() / n;
+        (mean, v.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0))
+    }
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+    let (mean_a, var_a) = mean_var(a);
+    let (mean_b, var_b) = mean_var(b);
+    let (se_a, se_b) = (var_a / a.len() as f64, var_b / b.len() as f64);
+    let se = se_a + se_b;
+    if se <= 0.0 {
+        return None;
+    }
+
+    let t = (mean_b - mean_a) / se.sqrt();
+    let df = se * se / (se_a * se_a / (a.len() - 1) as f64 + se_b * se_b / (b.len() - 1) as f64);
+    let p_value = regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5).clamp(0.0, 1.0);
+    Some(WelchTest { t, df, p_value })
+}
+
+/// Lanczos approximation of ln Γ(x) for x > 0.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut ser = 1.000_000_000_190_015;
+    for (i, c) in COEFFS.iter().enumerate() {
+        ser += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * ser / x).ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b) (continued fraction, Lentz's method).
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    // The continued fraction converges fast for x below the mean of the distribution.
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - regularized_incomplete_beta(1.0 - x, b, a);
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        for aa in [
+            m * (b - m) * x / ((a + m2 - 1.0) * (a + m2)),
+            -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0)),
+        ] {
+            d = 1.0 + aa * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + aa / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-14 {
+            break;
+        }
+    }
+    front * h / a
+}
+
+pub fn sensor_stats(rows: &[DataRow], query: &StatsQuery) -> StatsTable {
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: query.sensors.clone(),
+            start: query.start,
+            end: query.end,
+            resample: query.resample,
+            ..Default::default()
+        },
+    );
+
+    StatsTable {
+        start: query.start,
+        end: query.end,
+        percentiles: query.percentiles.clone(),
+        rows: set
+            .series
+            .iter()
+            .map(|s| describe(&s.sensor, &set.timestamps, &s.values, &query.percentiles))
+            .collect(),
+    }
+}
+
+impl StatsTable {
+    /// Render the table as CSV, one row per sensor.
+    pub fn to_csv(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        let mut header: Vec<String> = [
+            "Sensor", "Count", "Missing", "Min", "Min Time", "Max", "Max Time", "Mean", "Median", "Stddev",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        header.extend(self.percentiles.iter().map(|p| format!("P{p}")));
+        writer.write_record(&header)?;
+
+        let num = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_default();
+        let time = |ts: Option<i64>| {
+            ts.and_then(chrono::DateTime::from_timestamp_millis)
+                .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+                .unwrap_or_default()
+        };
+        for row in &self.rows {
+            let mut record = vec![
+                row.sensor.clone(),
+                row.count.to_string(),
+                row.missing.to_string(),
+                num(row.min),
+                time(row.min_at),
+                num(row.max),
+                time(row.max_at),
+                num(row.mean),
+                num(row.median),
+                num(row.stddev),
+            ];
+            // Percentiles are missing when the sensor has no samples; keep columns aligned.
+            for p in &self.percentiles {
+                record.push(num(row.percentiles.iter().find(|x| x.p == *p).map(|x| x.value)));
+            }
+            writer.write_record(&record)?;
+        }
+
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
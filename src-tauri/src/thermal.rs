@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataRow;
+use crate::phases::{segment_phases, PhaseKind, PhaseQuery};
+use crate::series::{extract_series, SeriesQuery};
+
+/// Fewest samples a fit is attempted on.
+const MIN_SAMPLES: usize = 8;
+
+/// Candidate time constants per decade in the coarse search.
+const GRID_PER_DECADE: usize = 40;
+
+/// Time to 95 % of the step, in time constants: ln(20).
+const TAU_TO_95: f64 = 2.995_732_273_553_991;
+
+fn default_max_points() -> usize {
+    300
+}
+
+/// First-order fit request. Without `start`, the fit window is the first full-load phase
+/// found on `load_sensor` (or the automatically chosen load sensor).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThermalFitQuery {
+    /// Temperature sensor to fit.
+    pub sensor: String,
+    /// Moment of the load step (unix ms).
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub load_sensor: Option<String>,
+    /// Points returned in [`ThermalFit::curve`].
+    #[serde(default = "default_max_points")]
+    pub max_points: usize,
+}
+
+/// `T(t) = asymptote - (asymptote - initial) * exp(-t / tau)`, `t` in seconds after the step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FirstOrderFit {
+    pub initial: f64,
+    pub asymptote: f64,
+    pub tau_s: f64,
+    pub sse: f64,
+}
+
+impl FirstOrderFit {
+    pub fn at(&self, t_s: f64) -> f64 {
+        self.asymptote - (self.asymptote - self.initial) * (-t_s / self.tau_s).exp()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FitPoint {
+    pub timestamp: i64,
+    pub measured: f64,
+    pub fitted: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThermalFit {
+    pub sensor: String,
+    pub start: i64,
+    pub end: i64,
+    /// Load sensor whose full-load phase set the window, when it was detected automatically.
+    pub load_sensor: Option<String>,
+    pub initial: f64,
+    /// Steady-state value.
+    pub asymptote: f64,
+    pub time_constant_s: f64,
+    pub time_to_95_s: f64,
+    /// Whether the window lasts long enough for the fit to reach 95 % of the step; if not,
+    /// the asymptote is an extrapolation.
+    pub reached_95: bool,
+    pub r_squared: f64,
+    pub rmse: f64,
+    pub samples: usize,
+    pub curve: Vec<FitPoint>,
+}
+
+/// Least-squares `initial` and `asymptote` for a fixed `tau`; the model is linear in them.
+fn fit_with_tau(t_s: &[f64], values: &[f64], tau_s: f64) -> Option<FirstOrderFit> {
+    let n = values.len() as f64;
+    let (mut se, mut see, mut sy, mut sey) = (0.0, 0.0, 0.0, 0.0);
+    for (t, y) in t_s.iter().zip(values) {
+        let e = (-t / tau_s).exp();
+        se += e;
+        see += e * e;
+        sy += y;
+        sey += e * y;
+    }
+    let det = n * see - se * se;
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    // values ≈ asymptote + b * e, with b = initial - asymptote
+    let b = (n * sey - se * sy) / det;
+    let asymptote = (sy - b * se) / n;
+    let mut fit = FirstOrderFit {
+        initial: asymptote + b,
+        asymptote,
+        tau_s,
+        sse: 0.0,
+    };
+    fit.sse = t_s.iter().zip(values).map(|(t, y)| (y - fit.at(*t)).powi(2)).sum();
+    Some(fit)
+}
+
+/// Fit a first-order step response to `values` sampled `t_s` seconds after the step: a
+/// log-spaced search over the time constant, refined by golden-section search.
+pub fn fit_first_order(t_s: &[f64], values: &[f64]) -> Option<FirstOrderFit> {
+    let span = t_s.iter().copied().fold(0.0, f64::max);
+    if values.len() < MIN_SAMPLES || span <= 0.0 {
+        return None;
+    }
+    let tau_min = (span / 1_000.0).max(0.1);
+    let tau_max = span * 20.0;
+    let decades = (tau_max / tau_min).log10();
+    let steps = (decades * GRID_PER_DECADE as f64).ceil() as usize;
+    let log_tau = |i: usize| tau_min.log10() + decades * i as f64 / steps as f64;
+
+    let (best, _) = (0..=steps)
+        .filter_map(|i| fit_with_tau(t_s, values, 10f64.powf(log_tau(i))).map(|fit| (i, fit)))
+        .min_by(|a, b| a.1.sse.total_cmp(&b.1.sse))?;
+
+    let sse_at = |lt: f64| {
+        fit_with_tau(t_s, values, 10f64.powf(lt)).map_or(f64::INFINITY, |f| f.sse)
+    };
+    let (mut lo, mut hi) = (log_tau(best.saturating_sub(1)), log_tau((best + 1).min(steps)));
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    for _ in 0..40 {
+        let a = hi - ratio * (hi - lo);
+        let b = lo + ratio * (hi - lo);
+        if sse_at(a) < sse_at(b) {
+            hi = b;
+        } else {
+            lo = a;
+        }
+    }
+    fit_with_tau(t_s, values, 10f64.powf((lo + hi) / 2.0))
+}
+
+/// First full-load phase of the load sensor, as `(start, end, sensor)`.
+fn detect_window(rows: &[DataRow], query: &ThermalFitQuery) -> Result<(i64, i64, String), String> {
+    let report = segment_phases(
+        rows,
+        &PhaseQuery {
+            sensor: query.load_sensor.clone(),
+            end: query.end,
+            ..Default::default()
+        },
+    )?;
+    report
+        .phases
+        .iter()
+        .find(|p| p.kind == PhaseKind::FullLoad)
+        .map(|p| (p.start, query.end.unwrap_or(p.end), report.sensor.clone()))
+        .ok_or_else(|| "未检测到满载阶段，请手动指定开始时间".to_string())
+}
+
+pub fn fit_thermal_response(rows: &[DataRow], query: &ThermalFitQuery) -> Result<ThermalFit, String> {
+    let (start, end, load_sensor) = match query.start {
+        Some(start) => (start, query.end, None),
+        None => {
+            let (start, end, sensor) = detect_window(rows, query)?;
+            (start, Some(end), Some(sensor))
+        }
+    };
+    if end.is_some_and(|end| end <= start) {
+        return Err("结束时间必须晚于开始时间".to_string());
+    }
+
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: vec![query.sensor.clone()],
+            start: Some(start),
+            end,
+            ..Default::default()
+        },
+    );
+    let samples: Vec<(i64, f64)> = set
+        .timestamps
+        .iter()
+        .zip(&set.series[0].values)
+        .filter_map(|(ts, v)| v.map(|v| (*ts, v)))
+        .collect();
+    if samples.len() < MIN_SAMPLES {
+        return Err(format!("传感器 {} 在所选时间段内的样本不足，无法拟合", query.sensor));
+    }
+
+    let t_s: Vec<f64> = samples.iter().map(|(ts, _)| (ts - start) as f64 / 1000.0).collect();
+    let values: Vec<f64> = samples.iter().map(|(_, v)| *v).collect();
+    let fit = fit_first_order(&t_s, &values)
+        .ok_or_else(|| "拟合失败，数据中没有可识别的升温或降温过程".to_string())?;
+
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let sst: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+    let last = samples.last().map_or(start, |(ts, _)| *ts);
+    let time_to_95_s = fit.tau_s * TAU_TO_95;
+
+    let stride = samples.len().div_ceil(query.max_points.max(2));
+    let curve = samples
+        .iter()
+        .zip(&t_s)
+        .step_by(stride)
+        .map(|((ts, v), t)| FitPoint {
+            timestamp: *ts,
+            measured: *v,
+            fitted: fit.at(*t),
+        })
+        .collect();
+
+    Ok(ThermalFit {
+        sensor: query.sensor.clone(),
+        start,
+        end: last,
+        load_sensor,
+        initial: fit.initial,
+        asymptote: fit.asymptote,
+        time_constant_s: fit.tau_s,
+        time_to_95_s,
+        reached_95: (last - start) as f64 / 1000.0 >= time_to_95_s,
+        r_squared: if sst > 0.0 { 1.0 - fit.sse / sst } else { 1.0 },
+        rmse: (fit.sse / values.len() as f64).sqrt(),
+        samples: samples.len(),
+        curve,
+    })
+}
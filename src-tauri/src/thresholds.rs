@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::DataRow;
+use crate::series::{extract_series, SeriesQuery};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Comparison {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Comparison {
+    pub fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Gt => value > threshold,
+            Comparison::Ge => value >= threshold,
+            Comparison::Lt => value < threshold,
+            Comparison::Le => value <= threshold,
+        }
+    }
+
+    /// Whether the most extreme value of an episode is its maximum (`>`/`>=`) or minimum.
+    fn peak_is_max(self) -> bool {
+        matches!(self, Comparison::Gt | Comparison::Ge)
+    }
+}
+
+/// Condition such as "`CPU Package [°C]` > 90 for at least 10 s".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdRule {
+    pub sensor: String,
+    pub op: Comparison,
+    pub threshold: f64,
+    /// Episodes shorter than this are dropped.
+    #[serde(default)]
+    pub min_duration_ms: i64,
+    /// A pause between samples longer than this ends the episode (e.g. between merged logs).
+    pub max_gap_ms: Option<i64>,
+}
+
+/// One interval where the rule held. `end` is the last sample that still satisfied it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Episode {
+    pub sensor: String,
+    pub start: i64,
+    pub end: i64,
+    pub duration_ms: i64,
+    /// Highest value for `>`/`>=` rules, lowest for `<`/`<=` rules.
+    pub peak: f64,
+    pub peak_at: i64,
+    pub samples: usize,
+}
+
+/// Streaming detector: feed samples in time order, collect finished episodes.
+///
+/// Used both for whole datasets and for rows appended while following a live log.
+#[derive(Debug, Clone)]
+pub struct ThresholdDetector {
+    rule: ThresholdRule,
+    open: Option<Episode>,
+    last_ts: Option<i64>,
+}
+
+impl ThresholdDetector {
+    pub fn new(rule: ThresholdRule) -> Self {
+        Self {
+            rule,
+            open: None,
+            last_ts: None,
+        }
+    }
+
+    pub fn rule(&self) -> &ThresholdRule {
+        &self.rule
+    }
+
+    /// The episode in progress, if the condition holds at the latest sample.
+    pub fn open_episode(&self) -> Option<&Episode> {
+        self.open.as_ref()
+    }
+
+    /// Feed one sample. Returns an episode when this sample ends one that lasted long enough.
+    /// Missing samples end the current episode.
+    pub fn push(&mut self, ts: i64, value: Option<f64>) -> Option<Episode> {
+        let gap = self
+            .last_ts
+            .zip(self.rule.max_gap_ms)
+            .is_some_and(|(last, max_gap)| ts - last > max_gap);
+        self.last_ts = Some(ts);
+
+        let mut finished = if gap { self.close() } else { None };
+
+        match value.filter(|v| self.rule.op.holds(*v, self.rule.threshold)) {
+            Some(v) => match &mut self.open {
+                Some(episode) => {
+                    episode.end = ts;
+                    episode.duration_ms = ts - episode.start;
+                    episode.samples += 1;
+                    let better = if self.rule.op.peak_is_max() { v > episode.peak } else { v < episode.peak };
+                    if better {
+                        episode.peak = v;
+                        episode.peak_at = ts;
+                    }
+                }
+                None => {
+                    self.open = Some(Episode {
+                        sensor: self.rule.sensor.clone(),
+                        start: ts,
+                        end: ts,
+                        duration_ms: 0,
+                        peak: v,
+                        peak_at: ts,
+                        samples: 1,
+                    });
+                }
+            },
+            None => finished = finished.or_else(|| self.close()),
+        }
+        finished
+    }
+
+    /// Close the episode in progress (end of data).
+    pub fn finish(&mut self) -> Option<Episode> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Option<Episode> {
+        self.open
+            .take()
+            .filter(|episode| episode.duration_ms >= self.rule.min_duration_ms)
+    }
+}
+
+/// Feed rows to several detectors and return the episodes that finished.
+pub fn feed_rows(detectors: &mut [ThresholdDetector], rows: &[DataRow]) -> Vec<Episode> {
+    let sensors: Vec<String> = detectors.iter().map(|d| d.rule.sensor.clone()).collect();
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors,
+            ..Default::default()
+        },
+    );
+
+    let mut finished = Vec::new();
+    for (detector, series) in detectors.iter_mut().zip(&set.series) {
+        for (&ts, &value) in set.timestamps.iter().zip(&series.values) {
+            finished.extend(detector.push(ts, value));
+        }
+    }
+    finished.sort_by_key(|e| e.start);
+    finished
+}
+
+/// Detectors for live monitoring, already fed with `rows` so that an episode in progress at the
+/// end of the existing data continues; episodes that already ended are not reported again.
+pub fn primed_detectors(rules: &[ThresholdRule], rows: &[DataRow]) -> Vec<ThresholdDetector> {
+    let mut detectors: Vec<ThresholdDetector> = rules.iter().cloned().map(ThresholdDetector::new).collect();
+    feed_rows(&mut detectors, rows);
+    detectors
+}
+
+/// All episodes of `rule` within an optional time window (unix ms, inclusive).
+pub fn detect_episodes(rows: &[DataRow], rule: &ThresholdRule, start: Option<i64>, end: Option<i64>) -> Vec<Episode> {
+    let set = extract_series(
+        rows,
+        &SeriesQuery {
+            sensors: vec![rule.sensor.clone()],
+            start,
+            end,
+            ..Default::default()
+        },
+    );
+
+    let mut detector = ThresholdDetector::new(rule.clone());
+    let mut episodes: Vec<Episode> = set
+        .timestamps
+        .iter()
+        .zip(&set.series[0].values)
+        .filter_map(|(&ts, &value)| detector.push(ts, value))
+        .collect();
+    episodes.extend(detector.finish());
+    episodes
+}
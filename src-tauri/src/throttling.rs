@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::parse_unit;
+use crate::dataset::row_timestamp;
+use crate::models::DataRow;
+use crate::series::{field_at, parse_bool, sample_step, sensor_fields};
+
+/// Word prefixes of HWiNFO `[Yes/No]` sensors that report throttling or a performance limiter,
+/// e.g. "Thermal Throttling (HTC)" or "Power Limit Exceeded". They only match at the start of a
+/// word, so "Unlimited" or "Nightcore" stay out. Other flags (e.g. drive failure/warning) are
+/// left out of the summary.
+const LIMITER_WORDS: &[&str] = &["throttl", "prochot", "htc", "limit"];
+
+/// Localized fragments of the same flags. Chinese names have no word boundaries.
+const LIMITER_FRAGMENTS: &[&str] = &["降频", "受限", "限制", "过热"];
+
+/// Time window (unix ms, inclusive) and, optionally, the exact flags to summarize.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThrottlingQuery {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// `[Yes/No]` sensors to use instead of the recognized throttling flags.
+    pub sensors: Option<Vec<String>>,
+}
+
+/// Interval during which a flag was set. `end` is the first sample where it was cleared
+/// (or the estimated end of the last sample), so `duration_ms` counts the whole sample period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlagInterval {
+    pub start: i64,
+    pub end: i64,
+    pub duration_ms: i64,
+}
+
+impl FlagInterval {
+    fn new(start: i64, end: i64) -> Self {
+        Self {
+            start,
+            end,
+            duration_ms: end - start,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlagSummary {
+    pub sensor: String,
+    /// Group path, top-level group (the device) first.
+    pub path: Vec<String>,
+    pub intervals: Vec<FlagInterval>,
+    pub throttled_ms: i64,
+    /// Share (0-1) of the window spent with the flag set.
+    pub throttled_ratio: f64,
+    pub longest: Option<FlagInterval>,
+    /// Start of the first interval.
+    pub first_at: Option<i64>,
+}
+
+/// Flags of one device, with their intervals merged.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceThrottling {
+    pub device: String,
+    pub flags: Vec<FlagSummary>,
+    /// Time with at least one flag set.
+    pub throttled_ms: i64,
+    pub throttled_ratio: f64,
+    pub longest: Option<FlagInterval>,
+    /// Flag whose first interval started earliest.
+    pub first_limiter: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThrottlingReport {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Covered time: first sample to the estimated end of the last one.
+    pub span_ms: i64,
+    pub devices: Vec<DeviceThrottling>,
+}
+
+/// Whether a `[Yes/No]` sensor name looks like a throttling or limiter flag.
+pub fn is_limiter_flag(sensor: &str) -> bool {
+    if parse_unit(sensor).as_deref() != Some("Yes/No") {
+        return false;
+    }
+    let name = sensor.to_lowercase();
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| LIMITER_WORDS.iter().any(|k| word.starts_with(k)))
+        || LIMITER_FRAGMENTS.iter().any(|k| name.contains(k))
+}
+
+/// Turn samples into set intervals. A missing sample ends the current interval.
+fn flag_intervals(timestamps: &[i64], values: &[Option<bool>], step: i64) -> Vec<FlagInterval> {
+    let mut intervals = Vec::new();
+    let mut open: Option<i64> = None;
+    for (&ts, &value) in timestamps.iter().zip(values) {
+        match (open, value == Some(true)) {
+            (None, true) => open = Some(ts),
+            (Some(start), false) => {
+                intervals.push(FlagInterval::new(start, ts));
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    if let (Some(start), Some(&last)) = (open, timestamps.last()) {
+        intervals.push(FlagInterval::new(start, last + step));
+    }
+    intervals
+}
+
+/// Union of possibly overlapping intervals, sorted by start.
+fn merge_intervals(mut intervals: Vec<FlagInterval>) -> Vec<FlagInterval> {
+    intervals.sort_by_key(|i| i.start);
+    let mut merged: Vec<FlagInterval> = Vec::new();
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => {
+                *last = FlagInterval::new(last.start, last.end.max(interval.end));
+            }
+            _ => merged.push(interval),
+        }
+    }
+    merged
+}
+
+fn longest(intervals: &[FlagInterval]) -> Option<FlagInterval> {
+    // Earliest wins on ties.
+    intervals.iter().copied().reduce(|a, b| if b.duration_ms > a.duration_ms { b } else { a })
+}
+
+fn ratio(part: i64, span: i64) -> f64 {
+    if span > 0 {
+        part as f64 / span as f64
+    } else {
+        0.0
+    }
+}
+
+/// Summarize throttling flags per device within the query window.
+pub fn throttling_summary(rows: &[DataRow], query: &ThrottlingQuery) -> ThrottlingReport {
+    let window: Vec<(i64, &DataRow)> = rows
+        .iter()
+        .filter_map(|row| row_timestamp(row).map(|ts| (ts, row)))
+        .filter(|(ts, _)| query.start.is_none_or(|s| *ts >= s) && query.end.is_none_or(|e| *ts <= e))
+        .collect();
+    let timestamps: Vec<i64> = window.iter().map(|(ts, _)| *ts).collect();
+    // Length of the last sample.
+    let step = sample_step(&timestamps);
+    let span_ms = match (timestamps.first(), timestamps.last()) {
+        (Some(first), Some(last)) => last + step - first,
+        _ => 0,
+    };
+
+    let flags: Vec<(Vec<String>, String)> = rows
+        .first()
+        .map(sensor_fields)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, sensor)| match &query.sensors {
+            Some(wanted) => wanted.contains(sensor),
+            None => is_limiter_flag(sensor),
+        })
+        .collect();
+
+    let mut devices: Vec<DeviceThrottling> = Vec::new();
+    for (path, sensor) in flags {
+        let values: Vec<Option<bool>> = window
+            .iter()
+            .map(|(_, row)| field_at(row, &path, &sensor).and_then(parse_bool))
+            .collect();
+        let intervals = flag_intervals(&timestamps, &values, step);
+        let throttled_ms = intervals.iter().map(|i| i.duration_ms).sum();
+        let summary = FlagSummary {
+            throttled_ratio: ratio(throttled_ms, span_ms),
+            longest: longest(&intervals),
+            first_at: intervals.first().map(|i| i.start),
+            throttled_ms,
+            intervals,
+            sensor,
+            path,
+        };
+
+        let device = summary.path[0].clone();
+        match devices.iter_mut().find(|d| d.device == device) {
+            Some(d) => d.flags.push(summary),
+            None => devices.push(DeviceThrottling {
+                device,
+                flags: vec![summary],
+                throttled_ms: 0,
+                throttled_ratio: 0.0,
+                longest: None,
+                first_limiter: None,
+            }),
+        }
+    }
+
+    for device in &mut devices {
+        let merged = merge_intervals(device.flags.iter().flat_map(|f| f.intervals.iter().copied()).collect());
+        device.throttled_ms = merged.iter().map(|i| i.duration_ms).sum();
+        device.throttled_ratio = ratio(device.throttled_ms, span_ms);
+        device.longest = longest(&merged);
+        device.first_limiter = device
+            .flags
+            .iter()
+            .filter_map(|f| f.first_at.map(|at| (at, &f.sensor)))
+            .min_by_key(|(at, _)| *at)
+            .map(|(_, sensor)| sensor.clone());
+    }
+
+    ThrottlingReport {
+        start: timestamps.first().copied(),
+        end: timestamps.last().copied(),
+        span_ms,
+        devices,
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::data_processor::DataProcessor;
+
+/// Bumped whenever a folder watch starts or stops so that the previous watch thread exits.
+static FOLDER_WATCH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn is_csv(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).ok()?.modified().ok()
+}
+
+fn is_valid_hwinfo_log(path: &Path) -> bool {
+    match path.to_str() {
+        Some(p) => DataProcessor::validate_csv_file(p).is_ok(),
+        None => false,
+    }
+}
+
+/// Header-only variant of [`is_valid_hwinfo_log`] for files seen on every write.
+fn has_hwinfo_header(path: &Path) -> bool {
+    match path.to_str() {
+        Some(p) => DataProcessor::validate_csv_header(p).is_ok(),
+        None => false,
+    }
+}
+
+/// Resolve symlinks, `..` and (on Windows) case so the same file always compares equal.
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Find the most recently modified CSV in `dir` that passes HWiNFO structure validation.
+pub fn newest_hwinfo_log(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let mut candidates: Vec<(SystemTime, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && is_csv(path))
+        .filter_map(|path| modified_time(&path).map(|t| (t, path)))
+        .collect();
+
+    // Newest first; only validate until the first acceptable file is found.
+    candidates.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    Ok(candidates
+        .into_iter()
+        .map(|(_, path)| path)
+        .find(|path| is_valid_hwinfo_log(path)))
+}
+
+/// Stop the active folder watch (if any).
+pub fn stop_folder_watch() {
+    FOLDER_WATCH_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Watch `dir` for new HWiNFO logs and call `on_new_log` whenever a newer valid CSV appears.
+///
+/// HWiNFO creates one CSV per logging session and may create it before the header is written,
+/// so files without a header yet are retried on their next modify event. Only the header is
+/// checked there, since every write HWiNFO makes triggers an event. `current` is the log that
+/// is already open, if any.
+pub fn start_folder_watch<F>(dir: PathBuf, current: Option<PathBuf>, mut on_new_log: F)
+where
+    F: FnMut(&Path) + Send + 'static,
+{
+    let generation = FOLDER_WATCH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || {
+        let (tx, rx) = channel();
+        let mut watcher: RecommendedWatcher = match RecommendedWatcher::new(tx, notify::Config::default()) {
+            Ok(w) => w,
+            Err(e) => {
+                log::error!("初始化日志目录监听失败: {e}");
+                return;
+            }
+        };
+
+        if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            log::error!("监听日志目录失败（{:?}）: {e}", dir);
+            return;
+        }
+
+        let mut current = current.as_deref().map(canonical);
+        loop {
+            if FOLDER_WATCH_GENERATION.load(Ordering::SeqCst) != generation {
+                log::info!("停止监听日志目录: {:?}", dir);
+                return;
+            }
+
+            let res = match rx.recv_timeout(Duration::from_secs(1)) {
+                Ok(res) => res,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+
+            let event = match res {
+                Ok(event) if event.kind.is_create() || event.kind.is_modify() => event,
+                Ok(_) => continue,
+                Err(e) => {
+                    log::error!("日志目录监听错误: {:?}", e);
+                    continue;
+                }
+            };
+
+            for path in event.paths.iter().filter(|p| is_csv(p)) {
+                let resolved = canonical(path);
+                if current.as_ref() == Some(&resolved) {
+                    continue;
+                }
+
+                // Only rotate forward: an older log being touched must not replace the current one.
+                let newer = match (&current, modified_time(path)) {
+                    (Some(cur), Some(t)) => modified_time(cur).map(|c| t >= c).unwrap_or(true),
+                    (None, Some(_)) => true,
+                    (_, None) => false,
+                };
+                if !newer || !has_hwinfo_header(path) {
+                    continue;
+                }
+
+                log::info!("检测到新的 HWiNFO 日志: {:?}", path);
+                on_new_log(path);
+                current = Some(resolved);
+            }
+        }
+    });
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use hwinfo_log_viewer_lib::aggregate::{
+    aggregate, aggregate_buckets, bucket_start, local_bucket_end, local_bucket_start, AggregateQuery, Aggregator,
+};
+use hwinfo_log_viewer_lib::series::{extract_series, SeriesQuery};
+
+const SENSOR: &str = "CPU 封装功率 [W]";
+
+#[test]
+fn test_bucket_start_alignment() {
+    assert_eq!(bucket_start(125_000, 60_000, 0), 120_000);
+    assert_eq!(bucket_start(120_000, 60_000, 0), 120_000);
+    assert_eq!(bucket_start(-1, 60_000, 0), -60_000);
+    // UTC+5:30: local hours start on the UTC half hour.
+    let offset = 19_800_000;
+    assert_eq!(bucket_start(3_600_000 + 10_000, 3_600_000, offset), 1_800_000);
+}
+
+#[test]
+fn test_local_buckets_across_dst_changes() {
+    // Other tests here do not depend on the zone, so switching it for the process is safe.
+    std::env::set_var("TZ", "Europe/Berlin");
+    const HOUR: i64 = 3_600_000;
+    let utc = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().timestamp_millis();
+
+    // Clocks go forward on 2024-03-31: that day has 23 hours and starts at local midnight.
+    let day = 24 * HOUR;
+    let start = local_bucket_start(utc("2024-03-31T12:00:00Z"), day);
+    assert_eq!(start, utc("2024-03-30T23:00:00Z"));
+    assert_eq!(local_bucket_end(start, day), utc("2024-03-31T22:00:00Z"));
+    assert_eq!(local_bucket_start(utc("2024-03-31T00:30:00Z"), day), start);
+    assert_eq!(local_bucket_start(utc("2024-03-31T21:59:00Z"), day), start);
+    // Clocks go back on 2024-10-27: 25 hours.
+    let start = local_bucket_start(utc("2024-10-27T12:00:00Z"), day);
+    assert_eq!(start, utc("2024-10-26T22:00:00Z"));
+    assert_eq!(local_bucket_end(start, day), utc("2024-10-27T23:00:00Z"));
+
+    // The repeated 02:00 hour forms two one-hour buckets.
+    let first = local_bucket_start(utc("2024-10-27T00:30:00Z"), HOUR);
+    let second = local_bucket_start(utc("2024-10-27T01:30:00Z"), HOUR);
+    assert_eq!((first, second), (utc("2024-10-27T00:00:00Z"), utc("2024-10-27T01:00:00Z")));
+    assert_eq!(local_bucket_end(first, HOUR), second);
+    // Hours after the change stay on the local hour.
+    assert_eq!(local_bucket_start(utc("2024-10-27T05:10:00Z"), HOUR), utc("2024-10-27T05:00:00Z"));
+}
+
+#[test]
+fn test_aggregators() {
+    let values = [4.0, 1.0, 3.0, 2.0];
+    assert_eq!(aggregate(&values, Aggregator::Mean), Some(2.5));
+    assert_eq!(aggregate(&values, Aggregator::Min), Some(1.0));
+    assert_eq!(aggregate(&values, Aggregator::Max), Some(4.0));
+    assert_eq!(aggregate(&values, Aggregator::Last), Some(2.0));
+    let p95 = aggregate(&values, Aggregator::P95).unwrap();
+    assert!((p95 - 3.85).abs() < 1e-9);
+    assert_eq!(aggregate(&[], Aggregator::Mean), None);
+}
+
+#[test]
+fn test_aggregate_buckets_cover_all_rows() {
+    let rows = common::load_rows("1.CSV");
+    let query = AggregateQuery {
+        sensors: vec![SENSOR.to_string()],
+        start: None,
+        end: None,
+        bucket_ms: 10_000,
+        aggregators: vec![Aggregator::Min, Aggregator::Mean, Aggregator::Max],
+    };
+    let table = aggregate_buckets(&rows, &query).unwrap();
+    let raw = extract_series(
+        &rows,
+        &SeriesQuery {
+            sensors: vec![SENSOR.to_string()],
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(table.columns.len(), 3);
+    assert_eq!(table.columns[1].aggregator, Aggregator::Mean);
+    assert_eq!(table.buckets.iter().map(|b| b.rows).sum::<usize>(), raw.timestamps.len());
+    assert!(table.buckets.windows(2).all(|w| w[0].end <= w[1].start));
+    for bucket in &table.buckets {
+        assert_eq!(bucket.end - bucket.start, 10_000);
+        assert_eq!(bucket.start % 1_000, 0);
+        let (min, mean, max) = (bucket.values[0].unwrap(), bucket.values[1].unwrap(), bucket.values[2].unwrap());
+        assert!(min <= mean && mean <= max);
+    }
+
+    let csv = table.to_csv().unwrap();
+    assert!(csv.starts_with(&format!("Bucket Start,Rows,{SENSOR} (min),{SENSOR} (mean),{SENSOR} (max)")));
+    assert_eq!(csv.lines().count(), table.buckets.len() + 1);
+
+    let too_fine = AggregateQuery { bucket_ms: 500, ..query };
+    assert!(aggregate_buckets(&rows, &too_fine).is_err());
+}
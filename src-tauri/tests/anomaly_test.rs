@@ -0,0 +1,135 @@
+mod common;
+
+use hwinfo_log_viewer_lib::anomaly::{detect_anomalies, sensor_anomalies, AnomalyKind, AnomalyQuery, Severity};
+use hwinfo_log_viewer_lib::models::{DataGroup, DataRow};
+
+/// 1 Hz samples around 50 with a spike at 100 s, a dropout at 200 s and a level shift at 250 s.
+fn synthetic() -> Vec<(i64, f64)> {
+    (0..300)
+        .map(|i| {
+            let mut v = 50.0 + (i as f64 * 0.7).sin() * 2.0;
+            match i {
+                100 => v = 80.0,
+                200 => v = 0.0,
+                i if i >= 250 => v += 30.0,
+                _ => {}
+            }
+            (i * 1_000, v)
+        })
+        .collect()
+}
+
+#[test]
+fn test_sensor_anomalies_kinds() {
+    let samples = synthetic();
+    let path = vec!["CPU".to_string()];
+    let found = sensor_anomalies(&samples, "CPU Power [W]", &path, &AnomalyQuery::default());
+    let at = |kind: AnomalyKind, ts: i64| found.iter().find(|a| a.kind == kind && a.start <= ts && ts <= a.end);
+
+    let spike = at(AnomalyKind::ZScore, 100_000).unwrap();
+    assert_eq!((spike.timestamp, spike.value, spike.severity), (100_000, 80.0, Severity::High));
+    assert!((spike.expected - 50.0).abs() < 1.0);
+    assert!(at(AnomalyKind::Mad, 100_000).is_some());
+
+    let dropout = at(AnomalyKind::Dropout, 200_000).unwrap();
+    assert_eq!((dropout.start, dropout.end), (200_000, 200_000));
+    // One missed sample is minor.
+    assert_eq!(dropout.severity, Severity::Low);
+    assert!((dropout.expected - 50.0).abs() < 2.0);
+
+    // The spike's rise and fall are merged into one step; the level shift is another.
+    assert_eq!(at(AnomalyKind::Step, 100_000).unwrap().end, 101_000);
+    assert_eq!(at(AnomalyKind::Step, 250_000).unwrap().timestamp, 250_000);
+    assert!(found.iter().all(|a| a.start >= 100_000));
+}
+
+#[test]
+fn test_sentinel_and_flat_sensor_steps() {
+    // A fixed clock that occasionally reads 65535 while disconnected.
+    let samples: Vec<(i64, f64)> = (0..120)
+        .map(|i| (i * 2_000, if i == 60 { 65535.0 } else { 4200.0 }))
+        .collect();
+    let query = AnomalyQuery {
+        sentinels: vec![65535.0],
+        ..Default::default()
+    };
+    let found = sensor_anomalies(&samples, "Clock [MHz]", &[], &query);
+    assert!(found.iter().any(|a| a.kind == AnomalyKind::Dropout && a.timestamp == 120_000));
+    assert!(found.iter().any(|a| a.kind == AnomalyKind::Step && a.start == 120_000));
+
+    let without = sensor_anomalies(&samples, "Clock [MHz]", &[], &AnomalyQuery::default());
+    assert!(without.iter().all(|a| a.kind != AnomalyKind::Dropout));
+
+    // A sensor gone for several samples is severe.
+    let long: Vec<(i64, f64)> = (0..120)
+        .map(|i| (i * 2_000, if (60..65).contains(&i) { 0.0 } else { 4200.0 }))
+        .collect();
+    let found = sensor_anomalies(&long, "Clock [MHz]", &[], &AnomalyQuery::default());
+    let dropout = found.iter().find(|a| a.kind == AnomalyKind::Dropout).unwrap();
+    assert_eq!((dropout.start, dropout.end), (120_000, 128_000));
+    assert_eq!(dropout.severity, Severity::High);
+}
+
+#[test]
+fn test_zscore_on_large_offset() {
+    // A clock near 4 GHz (in Hz) with millihertz jitter and one 1 Hz spike: the window
+    // variance must survive the offset.
+    let samples: Vec<(i64, f64)> = (0..300)
+        .map(|i| {
+            let jitter = (i as f64 * 0.7).sin() * 0.01;
+            (i * 1_000, 4.0e9 + jitter + if i == 150 { 1.0 } else { 0.0 })
+        })
+        .collect();
+    let found = sensor_anomalies(&samples, "Clock [Hz]", &[], &AnomalyQuery::default());
+    let spike = found.iter().find(|a| a.kind == AnomalyKind::ZScore).unwrap();
+    assert_eq!(spike.timestamp, 150_000);
+    assert!(found.iter().filter(|a| a.kind == AnomalyKind::ZScore).all(|a| a.start == 150_000));
+}
+
+#[test]
+fn test_yes_no_flags_are_skipped_by_unit() {
+    let rows: Vec<DataRow> = (0..300)
+        .map(|i| {
+            let mut row = DataRow::new();
+            let base = row.entry("base".to_string()).or_insert_with(DataGroup::new);
+            base.fields.insert("Timestamp".to_string(), (i * 1_000).to_string());
+            let cpu = row.entry("CPU".to_string()).or_insert_with(DataGroup::new);
+            let flag = if i == 100 { "Yes" } else { "No" };
+            cpu.fields.insert("Thermal Throttling [Yes/No]".to_string(), flag.to_string());
+            // Numeric 0/1 readings are still a sensor.
+            let parked = if i == 100 { "1" } else { "0" };
+            cpu.fields.insert("Parked Cores".to_string(), parked.to_string());
+            row
+        })
+        .collect();
+
+    let report = detect_anomalies(&rows, &AnomalyQuery::default()).unwrap();
+    assert_eq!(report.scanned_sensors, 1);
+    assert!(!report.anomalies.is_empty());
+    assert!(report.anomalies.iter().all(|a| a.sensor == "Parked Cores"));
+}
+
+#[test]
+fn test_detect_anomalies_on_dataset() {
+    let rows = common::load_rows("1.CSV");
+
+    let report = detect_anomalies(&rows, &AnomalyQuery::default()).unwrap();
+    assert!(report.scanned_sensors > 0);
+    assert!(report.anomalies.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+    assert!(report.anomalies.iter().all(|a| a.start <= a.timestamp && a.timestamp <= a.end));
+
+    let limited = AnomalyQuery {
+        max_results: 1,
+        z_threshold: 0.5,
+        ..Default::default()
+    };
+    let report = detect_anomalies(&rows, &limited).unwrap();
+    assert!(report.truncated);
+    assert_eq!(report.anomalies.len(), 1);
+
+    let invalid = AnomalyQuery {
+        mad_threshold: 0.0,
+        ..Default::default()
+    };
+    assert!(detect_anomalies(&rows, &invalid).is_err());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use hwinfo_log_viewer_lib::catalog::{build_catalog, field_columns, parse_unit, ValueType};
+
+#[test]
+fn test_parse_unit() {
+    assert_eq!(parse_unit("Core Clocks (avg) [MHz]").as_deref(), Some("MHz"));
+    assert_eq!(parse_unit("过热降频 (HTC) [Yes/No]").as_deref(), Some("Yes/No"));
+    assert_eq!(parse_unit("Total Errors"), None);
+}
+
+#[test]
+fn test_build_catalog_describes_sensors() {
+    let (rows, headers) = common::load_rows_with_headers("1.CSV");
+    let catalog = build_catalog(&rows, &headers);
+
+    assert!(!catalog.is_empty());
+    assert!(catalog.iter().all(|s| s.path.first().map(String::as_str) != Some("base")));
+
+    let htc = catalog
+        .iter()
+        .find(|s| s.sensor == "过热降频 (HTC) [Yes/No]")
+        .expect("throttling flag present");
+    assert_eq!(htc.value_type, ValueType::Boolean);
+    assert_eq!(htc.column, Some(291));
+    // Flags chart as 0/1.
+    assert!(htc.min.unwrap() >= 0.0 && htc.max.unwrap() <= 1.0);
+
+    let numeric = catalog
+        .iter()
+        .find(|s| s.value_type == ValueType::Numeric && s.samples == rows.len())
+        .expect("a fully populated numeric sensor");
+    assert_eq!(numeric.missing_ratio, 0.0);
+    assert!(numeric.min.unwrap() <= numeric.max.unwrap());
+    assert_eq!(headers[numeric.column.unwrap()], numeric.sensor);
+}
+
+#[test]
+fn test_repeated_sensor_names_get_their_own_columns() {
+    let columns: Vec<String> = ["Date", "Drive T [°C]", "Load [%]", "Drive T [°C]", "Drive T [°C]"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let field = |group: &str, name: &str| (vec![group.to_string()], name.to_string());
+
+    // One field per column: nth occurrence <-> nth column.
+    let fields = vec![
+        field("Drive 0", "Drive T [°C]"),
+        field("Drive 1", "Drive T [°C]"),
+        field("Drive 2", "Drive T [°C]"),
+    ];
+    assert_eq!(field_columns(&fields, &columns), vec![Some(1), Some(3), Some(4)]);
+
+    // Duplicates collapsed into one field hold the value of the last column.
+    let fields = vec![field("Drive", "Drive T [°C]"), field("CPU", "Load [%]"), field("CPU", "Clock [MHz]")];
+    assert_eq!(field_columns(&fields, &columns), vec![Some(4), Some(2), None]);
+
+    let (rows, headers) = common::load_rows_with_headers("1.CSV");
+    let catalog = build_catalog(&rows, &headers);
+    let drive = catalog.iter().find(|s| s.sensor == "磁盘温度 [℃]").unwrap();
+    let last = headers.iter().rposition(|c| c == "磁盘温度 [℃]").unwrap();
+    assert_ne!(headers.iter().position(|c| c == "磁盘温度 [℃]"), Some(last));
+    assert_eq!(drive.column, Some(last));
+}
@@ -0,0 +1,21 @@
+// Each test crate uses only some of these helpers.
+#![allow(dead_code)]
+
+use hwinfo_log_viewer_lib::data_processor::DataProcessor;
+use hwinfo_log_viewer_lib::models::{DataRow, GroupsConfig};
+
+/// Processor with the shipped group configuration.
+pub fn processor() -> DataProcessor {
+    DataProcessor::new(GroupsConfig::load_from_file("config/groups.toml").unwrap())
+}
+
+/// Rows of a sample log under `data/`, e.g. `"1.CSV"`.
+pub fn load_rows(file: &str) -> Vec<DataRow> {
+    processor().process_csv_file(&format!("data/{file}")).unwrap()
+}
+
+/// Rows of a sample log under `data/` together with its CSV header columns.
+pub fn load_rows_with_headers(file: &str) -> (Vec<DataRow>, Vec<String>) {
+    let (rows, follower) = processor().follow_csv_file(&format!("data/{file}")).unwrap();
+    (rows, follower.headers().to_vec())
+}
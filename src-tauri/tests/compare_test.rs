@@ -0,0 +1,152 @@
+mod common;
+
+use std::collections::HashMap;
+
+use hwinfo_log_viewer_lib::compare::{canonical_name, compare_runs, pair_sensors, Alignment, CompareQuery};
+use hwinfo_log_viewer_lib::dataset::row_timestamp;
+use hwinfo_log_viewer_lib::models::{DataGroup, DataRow};
+use hwinfo_log_viewer_lib::series::{field_at, find_field_path};
+use hwinfo_log_viewer_lib::thresholds::Comparison;
+
+const SENSOR: &str = "CPU 封装功率 [W]";
+
+#[test]
+fn test_pair_sensors_by_canonical_name() {
+    assert_eq!(canonical_name("CPU Package  [°C]"), canonical_name("cpu package [°C]"));
+
+    let field = |path: &str, name: &str| (vec![path.to_string()], name.to_string());
+    let a = vec![field("CPU", "CPU Package [°C]"), field("Fans", "Fan1 [RPM]")];
+    let b = vec![field("GPU", "GPU Power [W]"), field("CPU", "cpu package [°C]")];
+    let (pairs, only_a, only_b) = pair_sensors(&a, &b);
+    assert_eq!(pairs, vec![(a[0].clone(), b[1].clone())]);
+    assert_eq!(only_a, vec![a[1].clone()]);
+    assert_eq!(only_b, vec![b[0].clone()]);
+
+    // The same name on several devices pairs by group path first, then by name alone.
+    let a = vec![field("GPU 0", "Power [W]"), field("GPU 1", "Power [W]"), field("GPU 2", "Power [W]")];
+    let b = vec![field("GPU 1", "power [W]"), field("GPU 3", "Power [W]"), field("GPU 0", "Power [W]")];
+    let (pairs, only_a, only_b) = pair_sensors(&a, &b);
+    assert_eq!(
+        pairs,
+        vec![(a[0].clone(), b[2].clone()), (a[1].clone(), b[0].clone()), (a[2].clone(), b[1].clone())]
+    );
+    assert!(only_a.is_empty() && only_b.is_empty());
+}
+
+#[test]
+fn test_compare_runs_keeps_devices_with_the_same_sensor_name() {
+    let baseline = common::load_rows("1.CSV");
+    let path = find_field_path(&baseline[0], SENSOR).unwrap();
+    // A second device logging the same sensor name: 10 W above the first in run A, 13 W in run B.
+    let with_second_device = |rows: &[DataRow], offset: f64| -> Vec<DataRow> {
+        let mut rows = rows.to_vec();
+        for row in &mut rows {
+            let power: f64 = field_at(row, &path, SENSOR).unwrap().parse().unwrap();
+            let group = DataGroup {
+                fields: HashMap::from([(SENSOR.to_string(), (power + offset).to_string())]),
+                children: HashMap::new(),
+            };
+            row.insert("第二颗 CPU".to_string(), group);
+        }
+        rows
+    };
+    let rows_a = with_second_device(&baseline, 10.0);
+    let rows_b = with_second_device(&baseline, 13.0);
+
+    let query = CompareQuery {
+        sensors: Some(vec![SENSOR.to_string(), "No Such Sensor".to_string()]),
+        ..Default::default()
+    };
+    let report = compare_runs(&rows_a, &rows_b, &query).unwrap();
+    assert_eq!(report.unmatched_a, vec!["No Such Sensor".to_string()]);
+    assert_eq!(report.sensors.len(), 2);
+    let delta = |path: &[String]| {
+        let comparison = report.sensors.iter().find(|c| c.path_a == path).unwrap();
+        assert_eq!(comparison.sensor_a, SENSOR);
+        assert_eq!(comparison.path_b, path);
+        comparison.delta_mean.unwrap()
+    };
+    assert!(delta(&path).abs() < 1e-9);
+    assert!((delta(&["第二颗 CPU".to_string()]) - 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_compare_runs_aligned_on_elapsed_time() {
+    let baseline = common::load_rows("1.CSV");
+    // Run B: same log three hours later, with 5 W less package power.
+    let path = find_field_path(&baseline[0], SENSOR).unwrap();
+    let mut candidate = baseline.clone();
+    for row in &mut candidate {
+        let ts = row_timestamp(row).unwrap() + 3 * 3_600_000;
+        row.get_mut("base").unwrap().fields.insert("Timestamp".to_string(), ts.to_string());
+        let power: f64 = field_at(row, &path, SENSOR).unwrap().parse().unwrap();
+        let mut group = row.get_mut(&path[0]).unwrap();
+        for name in &path[1..] {
+            group = group.children.get_mut(name).unwrap();
+        }
+        group.fields.insert(SENSOR.to_string(), (power - 5.0).to_string());
+    }
+
+    let query = CompareQuery {
+        sensors: Some(vec![SENSOR.to_string(), "核心功率 (avg) [W]".to_string()]),
+        include_series: true,
+        ..Default::default()
+    };
+    let report = compare_runs(&baseline, &candidate, &query).unwrap();
+    assert_eq!(report.origin_b - report.origin_a, 3 * 3_600_000);
+    assert_eq!(report.sensors.len(), 2);
+
+    let power = &report.sensors[0];
+    assert_eq!(power.a.count, baseline.len());
+    assert!((power.delta_mean.unwrap() + 5.0).abs() < 1e-9);
+    assert!(power.delta_percent.unwrap() < 0.0);
+    let series_a = power.series_a.as_ref().unwrap();
+    assert_eq!(series_a.elapsed_ms[0], 0);
+    assert_eq!(series_a.elapsed_ms, power.series_b.as_ref().unwrap().elapsed_ms);
+
+    let unchanged = &report.sensors[1];
+    assert_eq!(unchanged.delta_mean, Some(0.0));
+    assert!(!unchanged.significant);
+
+    // A sync event that never fires in either run is an error.
+    let never = CompareQuery {
+        alignment: Alignment::SyncEvent {
+            sensor: SENSOR.to_string(),
+            op: Comparison::Gt,
+            threshold: 1e9,
+        },
+        ..Default::default()
+    };
+    assert!(compare_runs(&baseline, &candidate, &never).is_err());
+}
+
+#[test]
+fn test_compare_runs_aligned_on_sync_event() {
+    let rows = common::load_rows("1.CSV");
+    let set = hwinfo_log_viewer_lib::series::extract_series(
+        &rows,
+        &hwinfo_log_viewer_lib::series::SeriesQuery {
+            sensors: vec![SENSOR.to_string()],
+            ..Default::default()
+        },
+    );
+    // Pick the value of row 20 as the "load start" threshold; run B starts at row 10.
+    let values: Vec<f64> = set.series[0].values.iter().map(|v| v.unwrap()).collect();
+    let threshold = values[20] - 1e-9;
+    let first_hit = values.iter().position(|v| *v >= threshold).unwrap();
+
+    let query = CompareQuery {
+        alignment: Alignment::SyncEvent {
+            sensor: SENSOR.to_string(),
+            op: Comparison::Ge,
+            threshold,
+        },
+        sensors: Some(vec![SENSOR.to_string()]),
+        ..Default::default()
+    };
+    let candidate = rows[10..].to_vec();
+    let report = compare_runs(&rows, &candidate, &query).unwrap();
+    assert_eq!(report.origin_a, set.timestamps[first_hit]);
+    let expected_b = values[10..].iter().position(|v| *v >= threshold).unwrap() + 10;
+    assert_eq!(report.origin_b, set.timestamps[expected_b]);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use hwinfo_log_viewer_lib::correlation::{
+    correlation_matrix, cross_correlation, lag_analysis, pearson, ranks, spearman, CorrelationMethod, CorrelationQuery,
+    LagQuery,
+};
+
+#[test]
+fn test_pearson_and_spearman() {
+    let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+    let cubes: Vec<f64> = xs.iter().map(|x| x * x * x).collect();
+    let falling: Vec<f64> = xs.iter().map(|x| 10.0 - 2.0 * x).collect();
+
+    assert!((pearson(&xs, &falling).unwrap() + 1.0).abs() < 1e-12);
+    // Monotonic but not linear: only Spearman sees a perfect relation.
+    assert!(pearson(&xs, &cubes).unwrap() < 0.99);
+    assert!((spearman(&xs, &cubes).unwrap() - 1.0).abs() < 1e-12);
+
+    assert_eq!(ranks(&[3.0, 1.0, 3.0, 2.0]), vec![3.5, 1.0, 3.5, 2.0]);
+    assert_eq!(pearson(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0]), None);
+    assert_eq!(pearson(&[1.0, 2.0], &[1.0, 2.0]), None);
+}
+
+#[test]
+fn test_cross_correlation_finds_lag() {
+    // The follower repeats the leader three samples later.
+    let leader: Vec<f64> = (0..40).map(|i| ((i * i) % 17) as f64).collect();
+    let follower: Vec<Option<f64>> = (0..40).map(|i| if i < 3 { None } else { Some(leader[i - 3]) }).collect();
+    let leader: Vec<Option<f64>> = leader.into_iter().map(Some).collect();
+
+    let lags = cross_correlation(&leader, &follower, 6);
+    assert_eq!(lags.first().map(|l| l.0), Some(-6));
+    let (best_lag, best_r) = lags.iter().copied().fold((0, f64::MIN), |a, b| if b.1 > a.1 { b } else { a });
+    assert_eq!(best_lag, 3);
+    assert!((best_r - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_correlation_on_dataset() {
+    let rows = common::load_rows("1.CSV");
+    let sensors = vec![
+        "CPU 封装功率 [W]".to_string(),
+        "核心功率 (avg) [W]".to_string(),
+        "Missing Sensor".to_string(),
+    ];
+
+    let result = correlation_matrix(
+        &rows,
+        &CorrelationQuery {
+            sensors: sensors.clone(),
+            method: CorrelationMethod::Spearman,
+            ..Default::default()
+        },
+    );
+    assert_eq!(result.sensors, sensors);
+    assert_eq!(result.matrix[0][0], Some(1.0));
+    assert_eq!(result.matrix[0][1], result.matrix[1][0]);
+    assert!(result.matrix[0][1].unwrap() > 0.5);
+    assert_eq!(result.samples[0][0], rows.len());
+    assert_eq!(result.matrix[0][2], None);
+    assert_eq!(result.samples[2][2], 0);
+
+    let lag = lag_analysis(
+        &rows,
+        &LagQuery {
+            leader: sensors[0].clone(),
+            follower: sensors[0].clone(),
+            start: None,
+            end: None,
+            max_lag_ms: 10_000,
+        },
+    );
+    assert!(lag.step_ms > 0);
+    assert_eq!(lag.best_lag_ms, Some(0));
+    assert!(lag.lags.iter().all(|p| p.lag_ms.abs() <= 10_000 && p.lag_ms % lag.step_ms == 0));
+}
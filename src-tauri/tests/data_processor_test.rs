@@ -118,3 +118,48 @@ fn test_find_key_in_group() {
 
     println!("Data: {}", data);
 }
+
+#[test]
+fn test_follow_csv_file_parses_only_appended_rows() -> Result<(), Box<dyn std::error::Error>> {
+    use hwinfo_log_viewer_lib::data_processor::FollowUpdate;
+    use std::io::Write;
+
+    let config = GroupsConfig::load_from_file("config/groups.toml").unwrap();
+    let processor = DataProcessor::new(config);
+
+    let path = std::env::temp_dir().join(format!("hwinfo_follow_{}.CSV", std::process::id()));
+    let path_str = path.to_str().unwrap().to_string();
+    std::fs::write(
+        &path,
+        "Date,Time,\"Core VIDs (avg) [V]\",\n22.3.2025,21:36:49.335,1.400,\n22.3.2025,21:36:51.3",
+    )?;
+
+    // The partially written last line must not be consumed yet.
+    let (rows, mut follower) = processor.follow_csv_file(&path_str)?;
+    assert_eq!(rows.len(), 1);
+
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
+    write!(file, "43,1.387,\n22.3.2025,21:36:53.338,1.135,\n")?;
+    match processor.poll_appended(&mut follower)? {
+        FollowUpdate::Appended(rows) => {
+            assert_eq!(rows.len(), 2);
+            let base = rows[0].get("base").expect("base group should exist");
+            assert_eq!(base.fields.get("Time").map(|s| s.as_str()), Some("21:36:51.343"));
+            assert!(base.fields.contains_key("Timestamp"));
+        }
+        other => panic!("expected appended rows, got {other:?}"),
+    }
+
+    // HWiNFO writes the repeated header + parent-title row when logging stops.
+    write!(file, "Date,Time,\"Core VIDs (avg) [V]\",\n,,CPU [#0]: AMD Ryzen 9 5950X,\n")?;
+    match processor.poll_appended(&mut follower)? {
+        FollowUpdate::Reloaded(rows) => {
+            assert_eq!(rows.len(), 3);
+            assert!(rows[0].contains_key("CPU [#0]: AMD Ryzen 9 5950X"));
+        }
+        other => panic!("expected a full reload, got {other:?}"),
+    }
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use hwinfo_log_viewer_lib::dataset::DatasetRegistry;
+use hwinfo_log_viewer_lib::models::DataGroup;
+
+#[test]
+fn test_registry_keeps_datasets_apart() -> Result<(), Box<dyn std::error::Error>> {
+    let mut registry = DatasetRegistry::new();
+    let first = registry.insert("data/1.CSV".to_string(), common::load_rows("1.CSV"));
+    let second = registry.insert("data/1_EN.CSV".to_string(), common::load_rows("1_EN.CSV"));
+    assert_ne!(first, second);
+
+    let infos = registry.list();
+    assert_eq!(infos.len(), 2);
+    assert_eq!(registry.get(second).unwrap().rows.len(), 3);
+
+    let usage = registry.memory_usage();
+    assert_eq!(usage.datasets.len(), 2);
+    assert!(usage.datasets[0].bytes > usage.datasets[1].bytes);
+    assert_eq!(usage.total_bytes, usage.datasets.iter().map(|d| d.bytes).sum::<usize>());
+
+    // Closing one dataset must not affect the other, and IDs are never reused.
+    assert!(registry.remove(first).is_some());
+    assert!(!registry.contains(first));
+    assert!(registry.contains(second));
+    let third = registry.insert("data/1.CSV".to_string(), Vec::new());
+    assert!(third > second);
+
+    Ok(())
+}
+
+#[test]
+fn test_has_sensor_checks_columns_and_every_row() {
+    let (mut rows, columns) = common::load_rows_with_headers("1_EN.CSV");
+    rows[2]
+        .entry("Late".to_string())
+        .or_insert_with(DataGroup::new)
+        .fields
+        .insert("Written Later [W]".to_string(), "1".to_string());
+
+    let mut registry = DatasetRegistry::new();
+    let id = registry.insert_with_columns("data/1_EN.CSV".to_string(), columns, rows);
+    let dataset = registry.get(id).unwrap();
+    assert!(dataset.has_sensor("Core 0 VID [V]"));
+    assert!(dataset.has_sensor("Written Later [W]"));
+    assert!(!dataset.has_sensor("No Such Sensor"));
+}
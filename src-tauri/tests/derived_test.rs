@@ -0,0 +1,69 @@
+mod common;
+
+use hwinfo_log_viewer_lib::data_processor::DataProcessor;
+use hwinfo_log_viewer_lib::derived::{format_value, remove_from_rows, DerivedEngine};
+use hwinfo_log_viewer_lib::models::{DerivedSensor, GroupsConfig};
+use hwinfo_log_viewer_lib::series::find_field_path;
+
+#[test]
+fn test_derived_sensors_from_config() -> Result<(), Box<dyn std::error::Error>> {
+    let base = std::fs::read_to_string("config/groups.toml")?;
+    let config_path = std::env::temp_dir().join("hwinfo_derived_groups.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "{base}\n[[derived_sensors]]\nname = \"VID Spread [V]\"\nexpr = \"`Core 0 VID [V]` - `Core 1 VID [V]`\"\n\
+             \n[[derived_sensors]]\nname = \"VID Spread [mV]\"\nexpr = \"`VID Spread [V]` * 1000\"\ngroup = [\"CPU\", \"Core VIDs\"]\n"
+        ),
+    )?;
+
+    let config = GroupsConfig::load_from_file(config_path.to_str().unwrap())?;
+    assert_eq!(config.derived_sensors.len(), 2);
+    assert!(!config.groups.contains_key("derived_sensors"));
+
+    let rows = DataProcessor::new(config).process_csv_file("data/1_EN.CSV")?;
+    let row = &rows[0];
+    let vids = &row["CPU"].children["Core VIDs"];
+    let expected: f64 = vids.fields["Core 0 VID [V]"].parse::<f64>()? - vids.fields["Core 1 VID [V]"].parse::<f64>()?;
+    assert_eq!(row["Derived"].fields["VID Spread [V]"], format_value(expected));
+    assert_eq!(vids.fields["VID Spread [mV]"], format_value(expected * 1000.0));
+
+    std::fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_invalid_derived_config_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let config_path = std::env::temp_dir().join("hwinfo_bad_derived_groups.toml");
+    std::fs::write(
+        &config_path,
+        "[base]\nfields = [\"Date\", \"Time\"]\n\n[[derived_sensors]]\nname = \"Bad\"\nexpr = \"`A` +\"\n",
+    )?;
+    assert!(GroupsConfig::load_from_file(config_path.to_str().unwrap()).is_err());
+    std::fs::remove_file(config_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_runtime_derived_sensor_add_and_remove() -> Result<(), Box<dyn std::error::Error>> {
+    let mut rows = common::load_rows("1_EN.CSV");
+
+    let sensor = DerivedSensor {
+        name: "Double VID [V]".to_string(),
+        expr: "`Core VIDs (avg) [V]` * 2 + `Not Logged`".to_string(),
+        group: vec!["Checks".to_string()],
+    };
+    DerivedEngine::new(std::slice::from_ref(&sensor))?.apply(&mut rows);
+    // Missing inputs leave an empty value, but the sensor exists in every row.
+    assert!(rows.iter().all(|r| r["Checks"].fields["Double VID [V]"].is_empty()));
+
+    remove_from_rows(&mut rows, &sensor);
+    assert!(rows.iter().all(|r| !r.contains_key("Checks")));
+    assert!(find_field_path(&rows[0], "Double VID [V]").is_none());
+
+    assert_eq!(format_value(1.23456), "1.23456");
+    assert_eq!(format_value(0.0004), "0.0004");
+    assert_eq!(format_value(2.0), "2");
+    assert_eq!(format_value(-0.0), "0");
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use hwinfo_log_viewer_lib::downsample::{downsample_indices, Downsample};
+
+fn sine(n: usize) -> (Vec<i64>, Vec<Option<f64>>) {
+    let ts = (0..n as i64).map(|i| i * 1000).collect();
+    let values = (0..n).map(|i| Some((i as f64 / 50.0).sin())).collect();
+    (ts, values)
+}
+
+#[test]
+fn test_lttb_keeps_endpoints_and_target_count() {
+    let (ts, mut values) = sine(10_000);
+    values[5_000] = Some(100.0);
+
+    let kept = downsample_indices(&ts, &values, 500, Downsample::Lttb);
+    assert_eq!(kept.len(), 500);
+    assert_eq!(kept[0], 0);
+    assert_eq!(*kept.last().unwrap(), 9_999);
+    assert!(kept.windows(2).all(|w| w[0] < w[1]));
+    assert!(kept.contains(&5_000), "LTTB should keep an isolated spike");
+}
+
+#[test]
+fn test_min_max_keeps_extremes_and_gaps() {
+    let (ts, mut values) = sine(10_000);
+    values[1_234] = Some(-50.0);
+    for v in &mut values[7_000..7_100] {
+        *v = None;
+    }
+
+    let kept = downsample_indices(&ts, &values, 200, Downsample::MinMax);
+    assert!(kept.len() <= 202);
+    assert!(kept.contains(&1_234));
+    // The start of the gap is kept (as a missing sample) so the chart breaks the line there.
+    assert!(kept.contains(&7_000));
+    assert!(!kept.contains(&7_050));
+}
+
+#[test]
+fn test_small_series_is_untouched() {
+    let (ts, values) = sine(100);
+    let kept = downsample_indices(&ts, &values, 500, Downsample::Lttb);
+    assert_eq!(kept, (0..100).collect::<Vec<_>>());
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use hwinfo_log_viewer_lib::energy::{integrate, integrate_energy, watts_per_unit, EnergyQuery};
+use hwinfo_log_viewer_lib::phases::PhaseQuery;
+
+#[test]
+fn test_integrate_trapezoid_and_gaps() {
+    // A 0 -> 100 W ramp over 10 s, a missing sample, then a 60 s pause in logging.
+    let timestamps = vec![0, 10_000, 12_000, 14_000, 16_000, 76_000, 78_000];
+    let watts = vec![Some(0.0), Some(100.0), Some(100.0), None, Some(50.0), Some(50.0), Some(50.0)];
+
+    let energy = integrate(&timestamps, &watts, i64::MIN, i64::MAX, 10_000);
+    // 500 J for the ramp, 200 J at 100 W and 100 J at 50 W; the gap contributes nothing.
+    assert!((energy.joules - 800.0).abs() < 1e-9);
+    assert!((energy.watt_hours - 800.0 / 3600.0).abs() < 1e-9);
+    assert_eq!(energy.covered_ms, 14_000);
+    assert!((energy.average_w.unwrap() - 800.0 / 14.0).abs() < 1e-9);
+
+    let bridged = integrate(&timestamps, &watts, i64::MIN, i64::MAX, 120_000);
+    assert!((bridged.joules - 3_800.0).abs() < 1e-9);
+    assert_eq!(bridged.covered_ms, 74_000);
+
+    let window = integrate(&timestamps, &watts, 10_000, 14_000, 10_000);
+    assert!((window.joules - 200.0).abs() < 1e-9);
+    assert_eq!(integrate(&timestamps, &watts, 20_000, 30_000, 10_000).average_w, None);
+}
+
+#[test]
+fn test_watts_per_unit() {
+    assert_eq!(watts_per_unit("CPU 封装功率 [W]"), Some(1.0));
+    assert_eq!(watts_per_unit("USB Power [mW]"), Some(0.001));
+    assert_eq!(watts_per_unit("CPU 温度 [°C]"), None);
+}
+
+#[test]
+fn test_integrate_energy_on_dataset() {
+    let rows = common::load_rows("1.CSV");
+
+    let report = integrate_energy(&rows, &EnergyQuery::default()).unwrap();
+    assert!(!report.sensors.is_empty());
+    assert!(report.sensors.iter().all(|s| s.energy.joules > 0.0));
+    let sum: f64 = report.sensors.iter().map(|s| s.energy.joules).sum();
+    assert!((report.total.joules - sum).abs() < 1e-6);
+    assert!(report.phases.is_empty());
+
+    let by_phase = integrate_energy(
+        &rows,
+        &EnergyQuery {
+            phases: Some(PhaseQuery::default()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(by_phase.phase_sensor.is_some());
+    let phase_sum: f64 = by_phase.phases.iter().map(|p| p.total.joules).sum();
+    assert!((phase_sum - by_phase.total.joules).abs() < 1e-6 * by_phase.total.joules.max(1.0));
+
+    let not_power = EnergyQuery {
+        sensors: vec!["CPU 总使用率 [%]".to_string()],
+        ..Default::default()
+    };
+    assert!(integrate_energy(&rows, &not_power).is_err());
+}
@@ -0,0 +1,51 @@
+use hwinfo_log_viewer_lib::expr::Expr;
+
+fn eval(src: &str, lookup: impl Fn(&str) -> Option<f64>) -> Option<f64> {
+    Expr::parse(src).unwrap().eval(&lookup)
+}
+
+#[test]
+fn test_expr_precedence_and_functions() {
+    let none = |_: &str| None;
+    assert_eq!(eval("1 + 2 * 3", none), Some(7.0));
+    assert_eq!(eval("(1 + 2) * 3", none), Some(9.0));
+    assert_eq!(eval("-2 ^ 2", none), Some(-4.0));
+    assert_eq!(eval("2 ^ 3 ^ 2", none), Some(512.0));
+    assert_eq!(eval("max(1, 5, 3) - min(4, 2)", none), Some(3.0));
+    assert_eq!(eval("avg(2, 4) + abs(-1) + round(1.6)", none), Some(6.0));
+    assert_eq!(eval("clamp(120, 0, 100)", none), Some(100.0));
+    assert_eq!(eval("if(3 > 2, 10, 20)", none), Some(10.0));
+    assert_eq!(eval("1e3 / 4", none), Some(250.0));
+    assert_eq!(eval("1 / 0", none), None);
+}
+
+#[test]
+fn test_expr_sensors() {
+    let expr = Expr::parse("`CPU Package Power [W]` + `GPU Power [W]` * 1").unwrap();
+    assert_eq!(expr.sensors(), vec!["CPU Package Power [W]", "GPU Power [W]"]);
+
+    let lookup = |name: &str| match name {
+        "CPU Package Power [W]" => Some(88.5),
+        "GPU Power [W]" => Some(200.0),
+        _ => None,
+    };
+    assert_eq!(expr.eval(&lookup), Some(288.5));
+    assert_eq!(eval("`Missing` + 1", lookup), None);
+}
+
+#[test]
+fn test_expr_parse_errors() {
+    assert!(Expr::parse("1 +").is_err());
+    assert!(Expr::parse("(1 + 2").is_err());
+    assert!(Expr::parse("`unterminated").is_err());
+    assert!(Expr::parse("foo(1)").is_err());
+    assert!(Expr::parse("CPU + 1").is_err());
+    assert!(Expr::parse("pow(1)").is_err());
+    assert!(Expr::parse("1 2").is_err());
+
+    // Nesting is bounded instead of overflowing the stack.
+    assert!(Expr::parse(&format!("{}1{}", "(".repeat(40), ")".repeat(40))).is_ok());
+    let err = Expr::parse(&format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000))).unwrap_err();
+    assert!(err.message.contains("nested too deeply"));
+    assert!(Expr::parse(&"-".repeat(10_000)).is_err());
+}
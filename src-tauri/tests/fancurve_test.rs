@@ -0,0 +1,81 @@
+mod common;
+
+use hwinfo_log_viewer_lib::fancurve::{
+    fan_curve, find_oscillations, fit_piecewise, swing_pivots, temperature_trend, FanCurveQuery, Trend,
+};
+
+#[test]
+fn test_fit_piecewise_recovers_curve() {
+    // A typical curve: 800 RPM up to 50 °C, then 40 RPM per °C up to 2000 RPM at 80 °C.
+    let curve = |t: f64| (800.0 + (t - 50.0).max(0.0) * 40.0).min(2000.0);
+    let temperatures: Vec<f64> = (0..500).map(|i| 30.0 + i as f64 * 0.1).collect();
+    let fans: Vec<f64> = temperatures.iter().map(|t| curve(*t)).collect();
+
+    let fit = fit_piecewise(&temperatures, &fans, 6).unwrap();
+    assert_eq!(fit.breakpoints.len(), 7);
+    assert!(fit.rmse < 40.0, "rmse {}", fit.rmse);
+    for t in [35.0, 65.0, 78.0] {
+        assert!((fit.at(t) - curve(t)).abs() < 60.0, "{t}: {}", fit.at(t));
+    }
+    assert!(fit_piecewise(&[60.0; 20], &[1000.0; 20], 4).is_none());
+}
+
+#[test]
+fn test_trend_and_oscillations() {
+    // 1 Hz: temperature steady at 60 °C while the fan hunts 1000 <-> 1400 RPM every 20 s,
+    // then the temperature ramps up and the fan follows smoothly.
+    let timestamps: Vec<i64> = (0..400).map(|i| i * 1_000).collect();
+    let temperatures: Vec<f64> = (0..400)
+        .map(|i| if i < 200 { 60.0 } else { 60.0 + (i - 200) as f64 * 0.05 })
+        .collect();
+    let fans: Vec<f64> = (0..400)
+        .map(|i| match i {
+            0..=199 if (i / 10) % 2 == 0 => 1000.0,
+            0..=199 => 1400.0,
+            _ => 1400.0 + (i - 200) as f64,
+        })
+        .collect();
+
+    let trends = temperature_trend(&timestamps, &temperatures);
+    assert_eq!(trends[100], Trend::Steady);
+    assert_eq!(trends[300], Trend::Rising);
+
+    assert_eq!(swing_pivots(&[0.0, 1.0, 0.0, 5.0, 0.0, 2.0], 3.0), vec![0, 3, 4]);
+
+    let episodes = find_oscillations(&timestamps, &fans, &temperatures, 200.0, 60_000, 3);
+    assert_eq!(episodes.len(), 1);
+    let episode = &episodes[0];
+    assert!(episode.cycles >= 8);
+    assert!((episode.mean_period_ms - 20_000).abs() <= 1_000);
+    assert!((episode.amplitude - 400.0).abs() < 1e-9);
+    assert_eq!(episode.temperature_span, 0.0);
+
+    // Cycles slower than the limit are not hunting.
+    assert!(find_oscillations(&timestamps, &fans, &temperatures, 200.0, 15_000, 3).is_empty());
+}
+
+#[test]
+fn test_fan_curve_on_dataset() {
+    let rows = common::load_rows("1.CSV");
+    let query = FanCurveQuery {
+        fan_sensor: "CPU [RPM]".to_string(),
+        temperature_sensor: "CPU (Tctl/Tdie) [℃]".to_string(),
+        start: None,
+        end: None,
+        segments: 3,
+        max_points: 100,
+        min_amplitude: None,
+        max_period_ms: 60_000,
+        min_cycles: 3,
+    };
+
+    let report = fan_curve(&rows, &query).unwrap();
+    assert!(report.samples > 0);
+    assert!(!report.points.is_empty() && report.points.len() <= 100);
+    let bp = &report.curve.breakpoints;
+    assert!(bp.len() >= 2 && bp.windows(2).all(|w| w[0].temperature < w[1].temperature));
+    assert!(report.oscillations.iter().all(|o| o.start < o.end && o.cycles >= 3));
+
+    let invalid = FanCurveQuery { segments: 0, ..query };
+    assert!(fan_curve(&rows, &invalid).is_err());
+}
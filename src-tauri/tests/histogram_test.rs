@@ -0,0 +1,80 @@
+mod common;
+
+use hwinfo_log_viewer_lib::histogram::{auto_edges, histogram, sample_durations, HistogramQuery, Weighting};
+
+#[test]
+fn test_sample_durations_cap_gaps() {
+    // The 60 s pause is counted as one typical interval.
+    let durations = sample_durations(&[0, 2_000, 4_000, 64_000, 66_000]);
+    assert_eq!(durations, vec![2_000, 2_000, 2_000, 2_000, 2_000]);
+    assert_eq!(sample_durations(&[5_000, 6_000, 9_000]), vec![1_000, 3_000, 3_000]);
+}
+
+#[test]
+fn test_auto_edges() {
+    assert_eq!(auto_edges(&[0.0, 1.0, 1.0, 0.0], None), vec![0.0, 0.5, 1.0]);
+    assert_eq!(auto_edges(&[3.0, 3.0], None), vec![2.5, 3.5]);
+
+    let edges = auto_edges(&[10.0, 20.0, 30.0, 40.0], Some(3));
+    assert_eq!(edges.len(), 4);
+    assert_eq!((edges[0], edges[3]), (10.0, 40.0));
+    assert!(auto_edges(&[], None).is_empty());
+}
+
+#[test]
+fn test_histogram_time_residency() {
+    let rows = common::load_rows("1.CSV");
+    let sensor = "CPU 封装功率 [W]".to_string();
+
+    let by_time = histogram(
+        &rows,
+        &HistogramQuery {
+            sensor: sensor.clone(),
+            bins: Some(8),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(by_time.bins.len(), 8);
+    assert_eq!(by_time.total_count, rows.len());
+    assert_eq!(by_time.bins.iter().map(|b| b.count).sum::<usize>(), rows.len());
+    assert_eq!(by_time.bins.iter().map(|b| b.duration_ms).sum::<i64>(), by_time.total_ms);
+    assert!((by_time.bins.iter().map(|b| b.share).sum::<f64>() - 1.0).abs() < 1e-9);
+    assert_eq!(by_time.bins[0].lower, by_time.min.unwrap());
+    assert!(by_time.bins[7].count > 0, "the maximum lands in the last bin");
+
+    // Explicit edges in the middle of the range push samples into under/overflow.
+    let mid = (by_time.min.unwrap() + by_time.max.unwrap()) / 2.0;
+    let by_count = histogram(
+        &rows,
+        &HistogramQuery {
+            sensor: sensor.clone(),
+            edges: Some(vec![mid, by_time.max.unwrap()]),
+            weighting: Weighting::Count,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(by_count.bins.len(), 1);
+    assert_eq!(by_count.underflow.count + by_count.bins[0].count, rows.len());
+    assert_eq!(by_count.underflow.share, by_count.underflow.count as f64 / rows.len() as f64);
+
+    let bad = HistogramQuery {
+        sensor,
+        edges: Some(vec![10.0, 5.0]),
+        ..Default::default()
+    };
+    assert!(histogram(&rows, &bad).is_err());
+
+    // Flags get a No and a Yes bin.
+    let flag = histogram(
+        &rows,
+        &HistogramQuery {
+            sensor: "GPU 性能受限 (avg) [Yes/No]".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(flag.bins.len(), 2);
+    assert_eq!(flag.bins[1].share, 1.0);
+}
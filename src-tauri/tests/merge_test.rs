@@ -0,0 +1,86 @@
+mod common;
+
+use hwinfo_log_viewer_lib::catalog::build_catalog;
+use hwinfo_log_viewer_lib::dataset::row_timestamp;
+use hwinfo_log_viewer_lib::merge::{merge_logs, LogPart};
+use hwinfo_log_viewer_lib::models::DataRow;
+use hwinfo_log_viewer_lib::series::{field_at, find_field_path};
+
+const SENSOR: &str = "CPU 封装功率 [W]";
+
+fn remove_field(row: &mut DataRow, path: &[String], sensor: &str) {
+    let mut group = row.get_mut(&path[0]).unwrap();
+    for name in &path[1..] {
+        group = group.children.get_mut(name).unwrap();
+    }
+    group.fields.remove(sensor);
+}
+
+#[test]
+fn test_merge_unions_sensors_and_marks_gaps() {
+    let (rows, columns) = common::load_rows_with_headers("1.CSV");
+    let path = find_field_path(&rows[0], SENSOR).unwrap();
+    let ts: Vec<i64> = rows.iter().map(|r| row_timestamp(r).unwrap()).collect();
+
+    // The later log (passed first) comes from a HWiNFO session without the package power sensor.
+    let mut later = rows[40..].to_vec();
+    for row in &mut later {
+        remove_field(row, &path, SENSOR);
+    }
+    let parts = vec![
+        LogPart {
+            path: "b.csv".to_string(),
+            columns: columns.iter().filter(|c| *c != SENSOR).cloned().collect(),
+            rows: later,
+        },
+        LogPart {
+            path: "a.csv".to_string(),
+            columns: columns.clone(),
+            rows: rows[..30].to_vec(),
+        },
+    ];
+
+    let merged = merge_logs(parts);
+    assert_eq!(merged.rows.len(), 30 + rows.len() - 40);
+    assert_eq!(merged.sources[0].path, "a.csv");
+    assert_eq!(merged.sources[1].start, Some(ts[40]));
+    assert_eq!(merged.columns, columns);
+
+    assert_eq!(merged.gaps.len(), 1);
+    let gap = &merged.gaps[0];
+    assert_eq!((gap.start, gap.end, gap.duration_ms), (ts[29], ts[40], ts[40] - ts[29]));
+    assert_eq!((gap.before.as_str(), gap.after.as_str()), ("a.csv", "b.csv"));
+
+    let merged_ts: Vec<i64> = merged.rows.iter().map(|r| row_timestamp(r).unwrap()).collect();
+    assert!(merged_ts.windows(2).all(|w| w[0] <= w[1]));
+    // Rows of the later log carry the missing sensor as an empty value.
+    assert_eq!(field_at(&merged.rows[35], &path, SENSOR), Some(""));
+
+    let catalog = build_catalog(&merged.rows, &merged.columns);
+    let info = catalog.iter().find(|s| s.sensor == SENSOR).unwrap();
+    assert_eq!(info.samples, 30);
+    assert!(info.missing_ratio > 0.0);
+}
+
+#[test]
+fn test_merge_overlapping_logs_interleaves_rows() {
+    let (rows, columns) = common::load_rows_with_headers("1.CSV");
+    let parts = vec![
+        LogPart {
+            path: "a.csv".to_string(),
+            columns: columns.clone(),
+            rows: rows[..40].to_vec(),
+        },
+        LogPart {
+            path: "b.csv".to_string(),
+            columns,
+            rows: rows[20..].to_vec(),
+        },
+    ];
+
+    let merged = merge_logs(parts);
+    assert!(merged.gaps.is_empty());
+    assert_eq!(merged.rows.len(), 40 + rows.len() - 20);
+    let merged_ts: Vec<i64> = merged.rows.iter().map(|r| row_timestamp(r).unwrap()).collect();
+    assert!(merged_ts.windows(2).all(|w| w[0] <= w[1]));
+}
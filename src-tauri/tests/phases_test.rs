@@ -0,0 +1,93 @@
+mod common;
+
+use hwinfo_log_viewer_lib::phases::{default_thresholds, segment, segment_phases, PhaseKind, PhaseQuery, PhaseThresholds};
+
+const THRESHOLDS: PhaseThresholds = PhaseThresholds {
+    idle_below: 10.0,
+    load_above: 80.0,
+};
+
+#[test]
+fn test_segment_benchmark_run() {
+    // 1 Hz utilization: idle, a two-minute benchmark with a 3 s dip, then idle again.
+    let timestamps: Vec<i64> = (0..400).map(|i| i * 1_000).collect();
+    let levels: Vec<Option<f64>> = (0..400)
+        .map(|i| match i {
+            60..=179 if !(100..103).contains(&i) => Some(95.0),
+            100..=102 => Some(30.0),
+            150 => None,
+            _ => Some(5.0),
+        })
+        .collect();
+
+    let spans = segment(&timestamps, &levels, &THRESHOLDS, 10_000, 120_000);
+    let summary: Vec<(PhaseKind, usize, usize)> = spans.iter().map(|s| (s.kind, s.first, s.last)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (PhaseKind::Idle, 0, 59),
+            (PhaseKind::FullLoad, 60, 179),
+            (PhaseKind::Cooldown, 180, 299),
+            (PhaseKind::Idle, 300, 399),
+        ]
+    );
+
+    let no_cooldown = segment(&timestamps, &levels, &THRESHOLDS, 10_000, 0);
+    assert_eq!(no_cooldown.len(), 3);
+    assert_eq!(no_cooldown[2].kind, PhaseKind::Idle);
+    // Without a minimum duration the dip is its own light phase.
+    assert!(segment(&timestamps, &levels, &THRESHOLDS, 0, 0).iter().any(|s| s.kind == PhaseKind::Light));
+}
+
+#[test]
+fn test_segment_long_noisy_log() {
+    // Two hours at 10 Hz flickering between idle and light load every few samples.
+    let timestamps: Vec<i64> = (0..72_000).map(|i| i * 100).collect();
+    let levels: Vec<Option<f64>> = (0..72_000u64)
+        .map(|i| Some(if i.wrapping_mul(2_654_435_761) % 7 < 3 { 5.0 } else { 40.0 }))
+        .collect();
+
+    let spans = segment(&timestamps, &levels, &THRESHOLDS, 10_000, 0);
+    assert!(!spans.is_empty());
+    assert_eq!(spans[0].first, 0);
+    assert_eq!(spans.last().unwrap().last, 71_999);
+    for pair in spans.windows(2) {
+        assert_eq!(pair[0].last + 1, pair[1].first);
+        assert_ne!(pair[0].kind, pair[1].kind);
+        assert!(timestamps[pair[1].first] - timestamps[pair[0].first] >= 10_000);
+    }
+}
+
+#[test]
+fn test_default_thresholds() {
+    assert_eq!(default_thresholds("CPU 总使用率 [%]", &[1.0, 2.0]), THRESHOLDS);
+    let power = default_thresholds("CPU 封装功率 [W]", &[10.0, 110.0]);
+    assert!((power.idle_below - 30.0).abs() < 1.0);
+    assert!((power.load_above - 70.0).abs() < 1.0);
+}
+
+#[test]
+fn test_segment_phases_on_dataset() {
+    let rows = common::load_rows("1.CSV");
+    let query = PhaseQuery {
+        stats_sensors: vec!["CPU 封装功率 [W]".to_string()],
+        ..Default::default()
+    };
+    let report = segment_phases(&rows, &query).unwrap();
+
+    assert_eq!(report.sensor, "CPU 总使用率 [%]");
+    assert!(!report.phases.is_empty());
+    assert!(report.phases.windows(2).all(|w| w[0].end == w[1].start && w[0].kind != w[1].kind));
+    for phase in &report.phases {
+        assert_eq!(phase.stats.len(), 2);
+        assert_eq!(phase.stats[0].sensor, report.sensor);
+        assert!(phase.stats[1].count > 0);
+    }
+
+    let invalid = PhaseQuery {
+        idle_below: Some(90.0),
+        load_above: Some(50.0),
+        ..Default::default()
+    };
+    assert!(segment_phases(&rows, &invalid).is_err());
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use hwinfo_log_viewer_lib::dataset::row_timestamp;
+use hwinfo_log_viewer_lib::query::{query_rows, time_bounds, RowQuery};
+
+#[test]
+fn test_query_rows_time_window_and_pagination() {
+    let rows = common::load_rows("1_EN.CSV");
+    let bounds = time_bounds(&rows).expect("sample has timestamps");
+    assert_eq!(bounds.row_count, 3);
+    assert_eq!(bounds.start, row_timestamp(&rows[0]).unwrap());
+    assert_eq!(bounds.end, row_timestamp(&rows[2]).unwrap());
+
+    // Window drops the first row; pagination then skips one of the remaining two.
+    let query = RowQuery {
+        start: Some(bounds.start + 1),
+        offset: Some(1),
+        limit: Some(10),
+        ..Default::default()
+    };
+    let page = query_rows(&rows, &query);
+    assert_eq!(page.total, 2);
+    assert_eq!(page.rows.len(), 1);
+    assert_eq!(row_timestamp(&page.rows[0]), Some(bounds.end));
+}
+
+#[test]
+fn test_query_rows_sensor_selection_keeps_base() {
+    let rows = common::load_rows("1_EN.CSV");
+    let query = RowQuery {
+        sensors: Some(vec!["Core 0 VID [V]".to_string()]),
+        limit: Some(1),
+        ..Default::default()
+    };
+    let page = query_rows(&rows, &query);
+    assert_eq!(page.total, 3);
+
+    let row = &page.rows[0];
+    assert!(row["base"].fields.contains_key("Timestamp"));
+    let cpu = &row["CPU"];
+    assert!(cpu.fields.is_empty());
+    let vids = &cpu.children["Core VIDs"];
+    assert_eq!(vids.fields.len(), 1);
+    assert_eq!(vids.fields["Core 0 VID [V]"], "1.062");
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use hwinfo_log_viewer_lib::resample::{grid, resample_values, GapFill, ResampleMethod, ResampleSpec};
+use hwinfo_log_viewer_lib::series::{extract_series, SeriesQuery};
+use hwinfo_log_viewer_lib::stats::{sensor_stats, StatsQuery};
+
+const SENSOR: &str = "CPU 封装功率 [W]";
+
+fn resample(timestamps: &[i64], values: &[Option<f64>], spec: &ResampleSpec, max_gap_ms: i64) -> Vec<Option<f64>> {
+    let points = grid(timestamps, spec.interval_ms, None);
+    resample_values(timestamps, values, &points, spec, max_gap_ms)
+}
+
+#[test]
+fn test_grid_aligns_to_origin() {
+    assert_eq!(grid(&[1_000, 3_500], 1_000, None), vec![1_000, 2_000, 3_000]);
+    // Grid points before the first sample are skipped but stay on the origin's phase.
+    assert_eq!(grid(&[1_200, 3_500], 1_000, Some(0)), vec![2_000, 3_000]);
+    assert!(grid(&[], 1_000, None).is_empty());
+}
+
+#[test]
+fn test_resample_methods() {
+    let ts = [0, 2_000, 4_000];
+    let values = [Some(0.0), Some(10.0), Some(20.0)];
+    let at = |method| resample(&ts, &values, &ResampleSpec::new(500, method), 10_000);
+
+    assert_eq!(at(ResampleMethod::Linear)[..4], [Some(0.0), Some(2.5), Some(5.0), Some(7.5)]);
+    assert_eq!(at(ResampleMethod::Previous)[..4], [Some(0.0); 4]);
+    assert_eq!(at(ResampleMethod::Nearest)[..4], [Some(0.0), Some(0.0), Some(0.0), Some(10.0)]);
+    assert_eq!(at(ResampleMethod::Linear).len(), 9);
+
+    // Mean of each bucket [t, t + 2 s); missing samples are ignored.
+    let ts = [0, 500, 1_000, 1_500, 2_000, 2_500];
+    let values = [Some(1.0), Some(3.0), None, Some(5.0), Some(10.0), Some(20.0)];
+    let mean = resample(&ts, &values, &ResampleSpec::new(2_000, ResampleMethod::Mean), 10_000);
+    assert_eq!(mean, vec![Some(3.0), Some(15.0)]);
+}
+
+#[test]
+fn test_resample_gap_fill() {
+    // Samples 1 s apart with a 10 s hole between 2 s and 12 s.
+    let ts = [0, 1_000, 2_000, 12_000, 13_000];
+    let values = [Some(1.0), Some(2.0), Some(3.0), Some(13.0), Some(14.0)];
+    let mut spec = ResampleSpec::new(1_000, ResampleMethod::Linear);
+
+    let empty = resample(&ts, &values, &spec, 3_000);
+    assert_eq!(empty.len(), 14);
+    assert_eq!(empty[2], Some(3.0));
+    assert!(empty[3..12].iter().all(Option::is_none));
+    assert_eq!(empty[12], Some(13.0));
+
+    spec.fill = GapFill::Previous;
+    assert!(resample(&ts, &values, &spec, 3_000)[3..12].iter().all(|v| *v == Some(3.0)));
+    spec.fill = GapFill::Zero;
+    assert_eq!(resample(&ts, &values, &spec, 3_000)[7], Some(0.0));
+    spec.fill = GapFill::Linear;
+    assert_eq!(resample(&ts, &values, &spec, 3_000)[7], Some(8.0));
+    // A larger threshold does not treat the hole as a gap at all.
+    spec.fill = GapFill::Empty;
+    assert_eq!(resample(&ts, &values, &spec, 20_000)[7], Some(8.0));
+}
+
+#[test]
+fn test_resampled_series_and_stats() {
+    let rows = common::load_rows("1.CSV");
+    let spec = ResampleSpec::new(500, ResampleMethod::Previous);
+    assert!(spec.validate().is_ok());
+    assert!(ResampleSpec::new(1, ResampleMethod::Linear).validate().is_err());
+
+    let raw = extract_series(
+        &rows,
+        &SeriesQuery {
+            sensors: vec![SENSOR.to_string()],
+            ..Default::default()
+        },
+    );
+    let set = extract_series(
+        &rows,
+        &SeriesQuery {
+            sensors: vec![SENSOR.to_string()],
+            resample: Some(spec),
+            ..Default::default()
+        },
+    );
+    assert_eq!(set.source_count, raw.timestamps.len());
+    assert_eq!(set.timestamps[0], raw.timestamps[0]);
+    assert!(set.timestamps.windows(2).all(|w| w[1] - w[0] == 500));
+    assert_eq!(set.series[0].values.len(), set.timestamps.len());
+
+    let csv = set.to_csv().unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some(format!("Time,{SENSOR}").as_str()));
+    assert_eq!(lines.count(), set.timestamps.len());
+
+    // Sample-and-hold on a grid finer than the log keeps the extremes.
+    let query = |resample| StatsQuery {
+        sensors: vec![SENSOR.to_string()],
+        start: None,
+        end: None,
+        percentiles: vec![50.0],
+        resample,
+    };
+    let logged = sensor_stats(&rows, &query(None));
+    let resampled = sensor_stats(&rows, &query(Some(spec)));
+    assert!(resampled.rows[0].count > logged.rows[0].count);
+    assert_eq!(resampled.rows[0].max, logged.rows[0].max);
+}
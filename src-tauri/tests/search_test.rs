@@ -0,0 +1,90 @@
+use hwinfo_log_viewer_lib::data_processor::DataProcessor;
+use hwinfo_log_viewer_lib::pinyin::{is_hanzi, romanize, syllable};
+use hwinfo_log_viewer_lib::search::{search_sensors, MatchKind, SearchMode, SearchQuery};
+
+fn sensors() -> Vec<(Vec<String>, String)> {
+    [
+        ("CPU", "核心频率 [MHz]"),
+        ("CPU", "CPU 封装功率 [W]"),
+        ("CPU", "Core Clocks (avg) [MHz]"),
+        ("CPU", "CPU Core Load [%]"),
+        ("GPU", "GPU 温度 [°C]"),
+        ("Memory", "Memory Clock [MHz]"),
+    ]
+    .iter()
+    .map(|(group, name)| (vec![group.to_string()], name.to_string()))
+    .collect()
+}
+
+fn query(text: &str, mode: SearchMode) -> SearchQuery {
+    SearchQuery {
+        query: text.to_string(),
+        mode,
+        limit: None,
+    }
+}
+
+#[test]
+fn test_romanize_full_and_initials() {
+    let (full, initials) = romanize("CPU 核心频率").unwrap();
+    assert_eq!(full, "cpuhexinpinlv");
+    assert_eq!(initials, "cpuhxpl");
+    assert!(romanize("Core Clocks").is_none());
+    // Unknown characters break the name instead of shifting the initials.
+    assert_eq!(romanize("核龘心 [MHz]").unwrap(), ("he xinmhz".to_string(), "h xmhz".to_string()));
+    assert_eq!(romanize("核心 龘").unwrap(), ("hexin".to_string(), "hx".to_string()));
+    assert!(romanize("龘").is_none());
+}
+
+#[test]
+fn test_pinyin_covers_config_and_sample_headers() {
+    let mut texts = vec![std::fs::read_to_string("config/groups.toml").unwrap()];
+    for path in ["data/1.CSV", "data/1_EN.CSV"] {
+        // The header and the tail meta lines hold every sensor and group name.
+        let text = DataProcessor::decode_log_text(&std::fs::read(path).unwrap());
+        let lines: Vec<&str> = text.lines().collect();
+        texts.push(lines[0].to_string());
+        texts.extend(lines[lines.len().saturating_sub(2)..].iter().map(|l| l.to_string()));
+    }
+
+    let mut missing: Vec<char> = texts
+        .iter()
+        .flat_map(|t| t.chars())
+        .filter(|c| is_hanzi(*c) && syllable(*c).is_none())
+        .collect();
+    missing.sort_unstable();
+    missing.dedup();
+    assert!(missing.is_empty(), "no pinyin for {missing:?}");
+}
+
+#[test]
+fn test_search_pinyin_initials_and_full() {
+    let hits = search_sensors(&sensors(), &query("hxpl", SearchMode::Fuzzy)).unwrap();
+    assert_eq!(hits[0].sensor, "核心频率 [MHz]");
+    assert_eq!(hits[0].matched_by, MatchKind::Pinyin);
+
+    let hits = search_sensors(&sensors(), &query("gonglv", SearchMode::Substring)).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].sensor, "CPU 封装功率 [W]");
+}
+
+#[test]
+fn test_search_ranking_substring_before_fuzzy() {
+    let hits = search_sensors(&sensors(), &query("clock", SearchMode::Fuzzy)).unwrap();
+    assert_eq!(hits[0].matched_by, MatchKind::Substring);
+    assert!(hits.iter().take(2).all(|h| h.sensor.contains("Clock")));
+
+    let hits = search_sensors(&sensors(), &query("cpucl", SearchMode::Fuzzy)).unwrap();
+    assert_eq!(hits[0].sensor, "CPU Core Load [%]");
+    assert_eq!(hits[0].matched_by, MatchKind::Fuzzy);
+    assert!(search_sensors(&sensors(), &query("cpucl", SearchMode::Substring)).unwrap().is_empty());
+}
+
+#[test]
+fn test_search_regex() {
+    let hits = search_sensors(&sensors(), &query(r"^(gpu|memory)\b", SearchMode::Regex)).unwrap();
+    let names: Vec<&str> = hits.iter().map(|h| h.sensor.as_str()).collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"GPU 温度 [°C]"));
+    assert!(search_sensors(&sensors(), &query("(", SearchMode::Regex)).is_err());
+}
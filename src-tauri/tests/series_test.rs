@@ -0,0 +1,46 @@
+mod common;
+
+use hwinfo_log_viewer_lib::series::{extract_series, find_field_path, SeriesQuery};
+
+#[test]
+fn test_extract_series_parallel_arrays() {
+    let rows = common::load_rows("1_EN.CSV");
+    let query = SeriesQuery {
+        sensors: vec!["Core 0 VID [V]".to_string(), "No Such Sensor".to_string()],
+        ..Default::default()
+    };
+    let set = extract_series(&rows, &query);
+
+    assert_eq!(set.timestamps.len(), 3);
+    assert!(set.timestamps.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(set.skipped_rows, 0);
+    assert_eq!(set.series.len(), 2);
+    assert_eq!(set.series[0].values.len(), 3);
+    assert_eq!(set.series[0].values[0], Some(1.062));
+    assert!(set.series[1].values.iter().all(Option::is_none));
+}
+
+#[test]
+fn test_extract_series_time_window_and_path() {
+    let rows = common::load_rows("1_EN.CSV");
+    let all = extract_series(
+        &rows,
+        &SeriesQuery {
+            sensors: vec!["Core 0 VID [V]".to_string()],
+            ..Default::default()
+        },
+    );
+    let windowed = extract_series(
+        &rows,
+        &SeriesQuery {
+            sensors: vec!["Core 0 VID [V]".to_string()],
+            start: Some(all.timestamps[1]),
+            end: Some(all.timestamps[1]),
+            ..Default::default()
+        },
+    );
+    assert_eq!(windowed.timestamps, vec![all.timestamps[1]]);
+
+    let path = find_field_path(&rows[0], "Core 0 VID [V]").unwrap();
+    assert_eq!(path, vec!["CPU".to_string(), "Core VIDs".to_string()]);
+}
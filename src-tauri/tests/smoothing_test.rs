@@ -0,0 +1,60 @@
+mod common;
+
+use hwinfo_log_viewer_lib::series::{extract_series, SeriesQuery};
+use hwinfo_log_viewer_lib::smoothing::{smooth, Smoothing};
+
+#[test]
+fn test_moving_average_and_median() {
+    let ts = [0, 1_000, 2_000, 3_000, 4_000];
+    let values = [Some(1.0), Some(2.0), Some(30.0), None, Some(4.0)];
+
+    // 2 s centred window: the sample and its direct neighbours.
+    let sma = smooth(&ts, &values, &Smoothing::Sma { window_ms: 2_000 });
+    assert_eq!(sma, vec![Some(1.5), Some(11.0), Some(16.0), None, Some(4.0)]);
+
+    let median = smooth(&ts, &values, &Smoothing::Median { window_ms: 2_000 });
+    assert_eq!(median, vec![Some(1.5), Some(2.0), Some(16.0), None, Some(4.0)]);
+
+    let wide = smooth(&ts, &values, &Smoothing::Median { window_ms: 4_000 });
+    assert_eq!(wide[2], Some(3.0));
+}
+
+#[test]
+fn test_ema_uses_sample_spacing() {
+    let tau = 1_000;
+    let step = smooth(&[0, 1_000, 3_000], &[Some(0.0), Some(10.0), Some(10.0)], &Smoothing::Ema { tau_ms: tau });
+    let decay = |dt: f64| 1.0 - (-dt / tau as f64).exp();
+    assert_eq!(step[0], Some(0.0));
+    assert!((step[1].unwrap() - 10.0 * decay(1_000.0)).abs() < 1e-9);
+    // A longer interval closes more of the remaining distance.
+    let expected = step[1].unwrap() + (10.0 - step[1].unwrap()) * decay(2_000.0);
+    assert!((step[2].unwrap() - expected).abs() < 1e-9);
+
+    assert!(Smoothing::Ema { tau_ms: 0 }.validate().is_err());
+    assert!(Smoothing::Sma { window_ms: 5_000 }.validate().is_ok());
+}
+
+#[test]
+fn test_series_query_raw_and_smoothed_overlay() {
+    let rows = common::load_rows("1.CSV");
+    let sensor = "CPU 封装功率 [W]".to_string();
+
+    let query: SeriesQuery = serde_json::from_value(serde_json::json!({
+        "sensors": [sensor, sensor],
+        "smoothing": [null, {"method": "sma", "windowMs": 10000}],
+    }))
+    .unwrap();
+    let set = extract_series(&rows, &query);
+
+    assert_eq!(set.series[0].smoothing, None);
+    assert_eq!(set.series[1].smoothing, Some(Smoothing::Sma { window_ms: 10_000 }));
+    let raw: Vec<f64> = set.series[0].values.iter().flatten().copied().collect();
+    let smoothed: Vec<f64> = set.series[1].values.iter().flatten().copied().collect();
+    assert_eq!(raw.len(), smoothed.len());
+
+    // Smoothing narrows the spread but keeps the level.
+    let spread = |v: &[f64]| v.iter().copied().fold(f64::MIN, f64::max) - v.iter().copied().fold(f64::MAX, f64::min);
+    assert!(spread(&smoothed) < spread(&raw));
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    assert!((mean(&smoothed) - mean(&raw)).abs() < 0.1 * mean(&raw));
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use hwinfo_log_viewer_lib::stats::{describe, percentile, sensor_stats, welch_t_test, StatsQuery};
+
+#[test]
+fn test_describe_basic_statistics() {
+    let timestamps = [1000, 2000, 3000, 4000, 5000, 6000];
+    let values = [Some(4.0), Some(1.0), None, Some(3.0), Some(2.0), Some(5.0)];
+    let stats = describe("Power [W]", &timestamps, &values, &[50.0, 100.0]);
+
+    assert_eq!(stats.count, 5);
+    assert_eq!(stats.missing, 1);
+    assert_eq!((stats.min, stats.min_at), (Some(1.0), Some(2000)));
+    assert_eq!((stats.max, stats.max_at), (Some(5.0), Some(6000)));
+    assert_eq!(stats.mean, Some(3.0));
+    assert_eq!(stats.median, Some(3.0));
+    assert!((stats.stddev.unwrap() - 2.5f64.sqrt()).abs() < 1e-12);
+    assert_eq!(stats.percentiles[1].value, 5.0);
+}
+
+#[test]
+fn test_percentile_interpolates() {
+    let sorted = [10.0, 20.0, 30.0, 40.0];
+    assert_eq!(percentile(&sorted, 0.0), Some(10.0));
+    assert_eq!(percentile(&sorted, 50.0), Some(25.0));
+    assert!((percentile(&sorted, 99.0).unwrap() - 39.7).abs() < 1e-9);
+    assert_eq!(percentile(&[], 50.0), None);
+}
+
+#[test]
+fn test_sensor_stats_table_and_csv() {
+    let rows = common::load_rows("1_EN.CSV");
+    let query: StatsQuery =
+        serde_json::from_str(r#"{"sensors": ["Core 0 VID [V]", "No Such Sensor"]}"#).unwrap();
+
+    let table = sensor_stats(&rows, &query);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0].count, 3);
+    assert_eq!(table.rows[0].percentiles.len(), table.percentiles.len());
+    assert_eq!(table.rows[1].count, 0);
+    assert!(table.rows[1].mean.is_none());
+
+    let csv = table.to_csv().unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("Sensor,Count,Missing,Min"));
+    assert!(lines[0].ends_with("P99"));
+    assert_eq!(lines[2].split(',').count(), lines[0].split(',').count());
+}
+
+#[test]
+fn test_welch_t_test() {
+    let a = [27.5, 21.0, 19.0, 23.6, 17.0, 17.9, 16.9, 20.1, 21.9, 22.6, 23.1, 19.6, 19.0, 21.7, 21.4];
+    let b = [27.1, 22.0, 20.8, 23.4, 23.4, 23.5, 25.8, 22.0, 24.8, 20.2, 21.9, 22.1, 22.9, 20.5, 24.4];
+    let test = welch_t_test(&a, &b).unwrap();
+    assert!((test.t - 2.455356).abs() < 1e-5);
+    assert!((test.df - 24.988529).abs() < 1e-5);
+    assert!((test.p_value - 0.021378).abs() < 1e-5);
+
+    assert!(welch_t_test(&a, &a).unwrap().p_value > 0.999);
+    assert_eq!(welch_t_test(&[1.0, 1.0], &[2.0, 2.0]), None);
+    assert_eq!(welch_t_test(&[1.0], &b), None);
+}
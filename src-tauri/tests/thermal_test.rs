@@ -0,0 +1,69 @@
+mod common;
+
+use hwinfo_log_viewer_lib::dataset::row_timestamp;
+use hwinfo_log_viewer_lib::thermal::{fit_first_order, fit_thermal_response, ThermalFitQuery};
+
+#[test]
+fn test_fit_first_order_recovers_parameters() {
+    // 40 °C -> 85 °C with a 45 s time constant, 2 Hz, plus a small deterministic ripple.
+    let t_s: Vec<f64> = (0..1200).map(|i| i as f64 * 0.5).collect();
+    let values: Vec<f64> = t_s
+        .iter()
+        .map(|t| 85.0 - 45.0 * (-t / 45.0).exp() + (t * 1.3).sin() * 0.3)
+        .collect();
+
+    let fit = fit_first_order(&t_s, &values).unwrap();
+    assert!((fit.tau_s - 45.0).abs() < 1.0, "tau {}", fit.tau_s);
+    assert!((fit.asymptote - 85.0).abs() < 0.2);
+    assert!((fit.initial - 40.0).abs() < 0.5);
+
+    // Cooling curves fit the same way.
+    let cooling: Vec<f64> = t_s.iter().map(|t| 35.0 + 50.0 * (-t / 20.0).exp()).collect();
+    let fit = fit_first_order(&t_s, &cooling).unwrap();
+    assert!((fit.tau_s - 20.0).abs() < 0.1);
+    assert!((fit.asymptote - 35.0).abs() < 0.01);
+}
+
+#[test]
+fn test_fit_first_order_needs_samples() {
+    assert!(fit_first_order(&[0.0, 1.0, 2.0], &[1.0, 2.0, 3.0]).is_none());
+    assert!(fit_first_order(&[0.0; 20], &[1.0; 20]).is_none());
+}
+
+#[test]
+fn test_fit_thermal_response_on_dataset() {
+    let rows = common::load_rows("1.CSV");
+    let query = ThermalFitQuery {
+        sensor: "CPU (Tctl/Tdie) [℃]".to_string(),
+        start: None,
+        end: None,
+        load_sensor: None,
+        max_points: 50,
+    };
+
+    // The fixture never reaches full load, so the window cannot be detected automatically.
+    let err = fit_thermal_response(&rows, &query).unwrap_err();
+    assert!(err.contains("满载"), "{err}");
+
+    let start = row_timestamp(&rows[0]).unwrap();
+    let fit = fit_thermal_response(
+        &rows,
+        &ThermalFitQuery {
+            start: Some(start),
+            ..query.clone()
+        },
+    )
+    .unwrap();
+    assert_eq!((fit.start, fit.load_sensor.as_deref()), (start, None));
+    assert!(fit.time_constant_s > 0.0);
+    assert!((fit.time_to_95_s / fit.time_constant_s - 3.0).abs() < 0.01);
+    assert!(!fit.curve.is_empty() && fit.curve.len() <= 50);
+    assert!(fit.curve.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+
+    let reversed = ThermalFitQuery {
+        start: Some(2_000_000_000_000),
+        end: Some(1_000_000_000_000),
+        ..query
+    };
+    assert!(fit_thermal_response(&rows, &reversed).is_err());
+}
@@ -0,0 +1,69 @@
+use hwinfo_log_viewer_lib::thresholds::{Comparison, ThresholdDetector, ThresholdRule};
+
+fn rule(op: Comparison, threshold: f64, min_duration_ms: i64) -> ThresholdRule {
+    ThresholdRule {
+        sensor: "CPU Package [°C]".to_string(),
+        op,
+        threshold,
+        min_duration_ms,
+        max_gap_ms: None,
+    }
+}
+
+fn run(detector: &mut ThresholdDetector, samples: &[(i64, Option<f64>)]) -> Vec<hwinfo_log_viewer_lib::thresholds::Episode> {
+    let mut episodes: Vec<_> = samples.iter().filter_map(|&(ts, v)| detector.push(ts, v)).collect();
+    episodes.extend(detector.finish());
+    episodes
+}
+
+#[test]
+fn test_detector_finds_episodes_with_min_duration() {
+    let samples = [
+        (0, Some(85.0)),
+        (2_000, Some(91.0)),
+        (4_000, Some(95.0)),
+        (6_000, Some(93.0)),
+        (8_000, Some(80.0)),
+        (10_000, Some(92.0)), // too short
+        (12_000, Some(70.0)),
+    ];
+    let episodes = run(&mut ThresholdDetector::new(rule(Comparison::Gt, 90.0, 3_000)), &samples);
+
+    assert_eq!(episodes.len(), 1);
+    let e = &episodes[0];
+    assert_eq!((e.start, e.end, e.duration_ms), (2_000, 6_000, 4_000));
+    assert_eq!((e.peak, e.peak_at, e.samples), (95.0, 4_000, 3));
+}
+
+#[test]
+fn test_detector_below_rule_and_missing_samples() {
+    let samples = [
+        (0, Some(3.0)),
+        (1_000, Some(1.0)),
+        (2_000, None),
+        (3_000, Some(2.0)),
+        (4_000, Some(4.0)),
+    ];
+    let episodes = run(&mut ThresholdDetector::new(rule(Comparison::Lt, 5.0, 0)), &samples);
+
+    // The missing sample splits the run in two.
+    assert_eq!(episodes.len(), 2);
+    assert_eq!((episodes[0].start, episodes[0].end, episodes[0].peak), (0, 1_000, 1.0));
+    assert_eq!((episodes[1].start, episodes[1].end, episodes[1].peak), (3_000, 4_000, 2.0));
+}
+
+#[test]
+fn test_detector_streaming_keeps_open_episode() {
+    let mut with_gap = rule(Comparison::Ge, 50.0, 0);
+    with_gap.max_gap_ms = Some(5_000);
+    let mut detector = ThresholdDetector::new(with_gap);
+
+    assert!(detector.push(0, Some(60.0)).is_none());
+    assert!(detector.push(1_000, Some(70.0)).is_none());
+    assert_eq!(detector.open_episode().unwrap().duration_ms, 1_000);
+
+    // A pause longer than max_gap_ms ends the episode even though the value still holds.
+    let ended = detector.push(60_000, Some(65.0)).expect("gap closes the episode");
+    assert_eq!((ended.start, ended.end), (0, 1_000));
+    assert_eq!(detector.open_episode().unwrap().start, 60_000);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use hwinfo_log_viewer_lib::data_processor::{DataProcessor, LogEncoding};
+use hwinfo_log_viewer_lib::dataset::row_timestamp;
+use hwinfo_log_viewer_lib::models::DataRow;
+use hwinfo_log_viewer_lib::series::{field_at, find_field_path, parse_value};
+use hwinfo_log_viewer_lib::throttling::{is_limiter_flag, throttling_summary, ThrottlingQuery};
+
+const HTC: &str = "过热降频 (HTC) [Yes/No]";
+const PROCHOT: &str = "过热降频 (PROCHOT CPU) [Yes/No]";
+
+fn set_flag(row: &mut DataRow, path: &[String], sensor: &str, value: &str) {
+    let mut group = row.get_mut(&path[0]).unwrap();
+    for name in &path[1..] {
+        group = group.children.get_mut(name).unwrap();
+    }
+    group.fields.insert(sensor.to_string(), value.to_string());
+}
+
+#[test]
+fn test_gbk_yes_no_values_are_decoded() {
+    // "No,是" with the localized value in GBK, as written by HWiNFO on Chinese Windows.
+    let text = DataProcessor::decode_log_text(b"\xEF\xBB\xBF\xE6\xB8\xA9\xE5\xBA\xA6,\xb7\xf1,\xca\xc7\r\n");
+    assert_eq!(text, "温度,否,是\r\n");
+    // A legacy run directly followed by ASCII: "是" then "丂" (0x81 0x40, ASCII trail byte).
+    let text = DataProcessor::decode_log_text(b"\xEF\xBB\xBF\xca\xc7\x81\x40,No\n");
+    assert_eq!(text, "是丂,No\n");
+    // A stray byte before the value must not break the pairing of the bytes after it.
+    let text = DataProcessor::decode_log_text(b"\xEF\xBB\xBF\xff\xca\xc7,\xb7\xf1\n");
+    assert_eq!(text, "\u{FFFD}是,否\n");
+
+    let rows = common::load_rows("1.CSV");
+    let path = find_field_path(&rows[0], "GPU 性能受限 (avg) [Yes/No]").unwrap();
+    assert_eq!(field_at(&rows[0], &path, "GPU 性能受限 (avg) [Yes/No]"), Some("是"));
+    assert_eq!(parse_value("是"), Some(1.0));
+    assert_eq!(parse_value(" No "), Some(0.0));
+
+    assert!(is_limiter_flag(HTC));
+    assert!(!is_limiter_flag("磁盘故障 [Yes/No]"));
+    assert!(!is_limiter_flag("GPU Power Limit [W]"));
+    assert!(is_limiter_flag("Power Limit Exceeded [Yes/No]"));
+    assert!(is_limiter_flag("GPU Performance Limiters [Yes/No]"));
+    // Keywords inside other words are not limiter flags.
+    assert!(!is_limiter_flag("Unlimited Boost [Yes/No]"));
+    assert!(!is_limiter_flag("Nightcore Mode [Yes/No]"));
+}
+
+#[test]
+fn test_log_encoding_is_chosen_per_file() {
+    // "状态,频率,丂" in GBK: the first two are also valid UTF-8 byte sequences, the extension
+    // character has an ASCII trail byte.
+    let gbk = b"\xd7\xb4\xcc\xac,\xc6\xb5\xc2\xca,\x81\x40\r\n";
+    assert_eq!(LogEncoding::detect(gbk), LogEncoding::Gbk);
+    assert_eq!(DataProcessor::decode_log_text(gbk), "状态,频率,丂\r\n");
+
+    let utf8 = "状态,频率\n".as_bytes();
+    assert_eq!(LogEncoding::detect(utf8), LogEncoding::Utf8);
+    assert_eq!(DataProcessor::decode_log_text(utf8), "状态,频率\n");
+    // Cut in the middle of the last character while HWiNFO is writing.
+    assert_eq!(LogEncoding::detect(&utf8[..utf8.len() - 2]), LogEncoding::Utf8);
+
+    // A UTF-8 header decides even when GBK "是"/"否" values follow; the names stay intact.
+    let mixed = b"\r\n\xE7\x8A\xB6\xE6\x80\x81,\xE9\xA2\x91\xE7\x8E\x87\r\n\xca\xc7,\xb7\xf1\r\n";
+    assert_eq!(LogEncoding::detect(mixed), LogEncoding::Utf8);
+    assert_eq!(DataProcessor::decode_log_text(mixed), "\r\n状态,频率\r\n是,否\r\n");
+}
+
+#[test]
+fn test_throttling_summary_per_device() {
+    let mut rows = common::load_rows("1.CSV");
+    let htc_path = find_field_path(&rows[0], HTC).unwrap();
+    let prochot_path = find_field_path(&rows[0], PROCHOT).unwrap();
+    assert_eq!(htc_path[0], prochot_path[0]);
+    for i in (10..15).chain(40..42) {
+        set_flag(&mut rows[i], &htc_path, HTC, "是");
+    }
+    for row in &mut rows[12..20] {
+        set_flag(row, &prochot_path, PROCHOT, "是");
+    }
+    let ts: Vec<i64> = rows.iter().map(|r| row_timestamp(r).unwrap()).collect();
+
+    let report = throttling_summary(&rows, &ThrottlingQuery::default());
+    let cpu = report.devices.iter().find(|d| d.device == htc_path[0]).unwrap();
+    assert!(cpu.flags.iter().all(|f| is_limiter_flag(&f.sensor)));
+
+    let htc = cpu.flags.iter().find(|f| f.sensor == HTC).unwrap();
+    assert_eq!(htc.intervals.len(), 2);
+    assert_eq!((htc.intervals[0].start, htc.intervals[0].end), (ts[10], ts[15]));
+    assert_eq!(htc.throttled_ms, ts[15] - ts[10] + ts[42] - ts[40]);
+    assert_eq!(htc.first_at, Some(ts[10]));
+
+    // Overlapping HTC and PROCHOT intervals are merged for the device.
+    assert_eq!(cpu.first_limiter.as_deref(), Some(HTC));
+    assert_eq!(cpu.throttled_ms, ts[20] - ts[10] + ts[42] - ts[40]);
+    let longest = cpu.longest.unwrap();
+    assert_eq!((longest.start, longest.end), (ts[10], ts[20]));
+    assert!((cpu.throttled_ratio - cpu.throttled_ms as f64 / report.span_ms as f64).abs() < 1e-12);
+
+    // The GPU reports "performance limited" for the whole log.
+    let gpu = report
+        .devices
+        .iter()
+        .find(|d| d.flags.iter().any(|f| f.sensor == "GPU 性能受限 (avg) [Yes/No]"))
+        .unwrap();
+    assert_eq!(gpu.throttled_ms, report.span_ms);
+
+    // A window after the HTC episodes sees only the PROCHOT tail.
+    let query = ThrottlingQuery {
+        start: Some(ts[16]),
+        end: Some(ts[30]),
+        sensors: Some(vec![HTC.to_string(), PROCHOT.to_string()]),
+    };
+    let report = throttling_summary(&rows, &query);
+    assert_eq!(report.devices.len(), 1);
+    assert_eq!(report.devices[0].first_limiter.as_deref(), Some(PROCHOT));
+    assert_eq!(report.devices[0].throttled_ms, ts[20] - ts[16]);
+}
@@ -0,0 +1,84 @@
+use std::io::Write;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use hwinfo_log_viewer_lib::data_processor::DataProcessor;
+use hwinfo_log_viewer_lib::watcher::{newest_hwinfo_log, start_folder_watch, stop_folder_watch};
+
+#[test]
+fn test_newest_hwinfo_log_skips_invalid_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("hwinfo_folder_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+
+    let older = dir.join("older.CSV");
+    std::fs::write(&older, "Date,Time,\"Core VIDs (avg) [V]\",\n22.3.2025,21:36:49.335,1.400,\n")?;
+    std::thread::sleep(std::time::Duration::from_millis(20));
+
+    // HWiNFO has just created the file of the next session but not written the header yet.
+    let newest = dir.join("newest.CSV");
+    std::fs::write(&newest, "")?;
+    std::fs::write(dir.join("notes.txt"), "Date,Time\n")?;
+
+    assert_eq!(newest_hwinfo_log(&dir)?, Some(older.clone()));
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    std::fs::write(&newest, "Date,Time,\"Core VIDs (avg) [V]\",\n22.3.2025,22:00:00.000,1.100,\n")?;
+    assert_eq!(newest_hwinfo_log(&dir)?, Some(newest));
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_header_check_ignores_an_unfinished_tail() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("hwinfo_header_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("growing.CSV");
+    let p = path.to_str().unwrap();
+
+    std::fs::write(&path, "")?;
+    assert!(DataProcessor::validate_csv_header(p).is_err());
+
+    // Header still being written, after blank lines.
+    std::fs::write(&path, "\r\n\r\nDate,Time,\"Core VIDs")?;
+    assert!(DataProcessor::validate_csv_header(p).is_ok());
+
+    // A parent-title line without the repeated header fails the full check only.
+    std::fs::write(&path, "\u{FEFF}Date,Time,\"Core VIDs (avg) [V]\",\n,,\"CPU [#0]\",\n")?;
+    assert!(DataProcessor::validate_csv_header(p).is_ok());
+    assert!(DataProcessor::validate_csv_file(p).is_err());
+
+    std::fs::write(&path, "22.3.2025,21:36:49.335,1.400,\nDate,Time,\n")?;
+    assert!(DataProcessor::validate_csv_header(p).is_err());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_folder_watch_matches_the_current_log_by_canonical_path() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("hwinfo_watch_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub"))?;
+    let current = dir.join("current.CSV");
+    std::fs::write(&current, "Date,Time,\"Core VIDs (avg) [V]\",\n22.3.2025,21:36:49.335,1.400,\n")?;
+
+    let (tx, rx) = channel();
+    // Spelled differently from the paths the watcher reports.
+    let spelled = dir.join("sub").join("..").join("current.CSV");
+    start_folder_watch(dir.clone(), Some(spelled), move |path| {
+        tx.send(path.file_name().unwrap().to_string_lossy().to_string()).unwrap();
+    });
+    std::thread::sleep(Duration::from_millis(200));
+
+    let mut file = std::fs::OpenOptions::new().append(true).open(&current)?;
+    writeln!(file, "22.3.2025,21:36:51.335,1.410,")?;
+    drop(file);
+    std::fs::write(dir.join("next.CSV"), "Date,Time,\"Core VIDs (avg) [V]\",\n")?;
+
+    let opened = rx.recv_timeout(Duration::from_secs(5))?;
+    stop_folder_watch();
+    assert_eq!(opened, "next.CSV");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
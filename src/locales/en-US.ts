@@ -16,7 +16,9 @@ export default {
     nav: {
         home: 'Home',
         sensorData: 'Sensors',
-        settings: 'Settings'
+        settings: 'Settings',
+        analysis: 'Analysis',
+        throttling: 'Throttling'
     },
     dashboard: {
         welcomeTitle: 'Welcome to HWInfo Log Viewer',
@@ -33,6 +35,10 @@ export default {
         processingCsv: 'Processing CSV…',
         csvProcessDone: 'CSV processed',
         csvProcessFailed: 'Process failed: {error}',
+        mergeCsv: 'Merge logs…',
+        mergingCsv: 'Merging {count} logs…',
+        mergeDone: 'Merged {count} logs into one dataset',
+        mergeFailed: 'Merge failed: {error}',
         selectCsvFailed: 'Failed to choose CSV: {error}',
         selectCsvSuccess: 'CSV selected: {path}'
     },
@@ -52,14 +58,222 @@ export default {
         csvProcessFailed: 'CSV processing failed: {error}',
         selectCsvFailed: 'Failed to choose CSV: {error}',
         selectCsvSuccess: 'CSV selected: {path}',
-        dataCleared: 'Data cleared'
+        dataCleared: 'Data cleared',
+
+        watchFolder: 'Watch log folder',
+        stopWatchFolder: 'Stop watching',
+        watchingFolder: 'Watching folder (newest log opens automatically)',
+        watchFolderOpened: 'Opened newest log: {path}',
+        watchFolderWaiting: 'No HWiNFO log in this folder yet; waiting for a new one',
+        watchFolderFailed: 'Failed to watch folder: {error}'
+    },
+    derived: {
+        title: 'Derived sensors',
+        hint: 'Compute new sensors from existing ones, e.g. `CPU Package Power [W]` + `GPU Power [W]`. Quote sensor names with backticks; functions: abs, sqrt, round, floor, ceil, min, max, avg, sum, pow, clamp, if.',
+        namePlaceholder: 'Name, e.g. System Power [W]',
+        exprPlaceholder: 'Expression',
+        groupPlaceholder: 'Group path, e.g. System/Power (default: Derived)',
+        add: 'Add',
+        remove: 'Remove',
+        fromConfig: 'From groups.toml',
+        added: 'Derived sensor "{name}" added',
+        addFailed: 'Failed to add derived sensor: {error}',
+        removeFailed: 'Failed to remove derived sensor: {error}'
+    },
+    analysis: {
+        correlationTab: 'Correlation',
+        histogramTab: 'Histogram',
+        compareTab: 'A/B compare',
+        aggregateTab: 'Time buckets',
+        anomalyTab: 'Anomalies',
+        phasesTab: 'Load phases',
+        energyTab: 'Energy',
+        thermalTab: 'Heat soak',
+        fanCurveTab: 'Fan curve',
+        sensorsPlaceholder: 'Select sensors',
+        compute: 'Compute',
+        failed: 'Analysis failed: {error}',
+        aggregate: {
+            title: 'Time-bucket summary',
+            export: 'Export CSV',
+            seconds: '{n} s',
+            minutes: '{n} min',
+            hours: '{n} h',
+            bucketStart: 'Bucket start',
+            rows: 'Rows',
+            mean: 'Mean',
+            min: 'Min',
+            max: 'Max',
+            last: 'Last',
+            p95: 'P95',
+            exported: 'Buckets exported to {path}'
+        },
+        anomaly: {
+            title: 'Spikes and anomalies',
+            hint: 'Rolling z-score, MAD outliers, sudden steps and dropouts (0 or a sentinel value). Click a row to open the sensor at that moment.',
+            sensorsPlaceholder: 'Sensors to scan (all numeric sensors when empty)',
+            window: 'Window',
+            sentinelsPlaceholder: 'Dropout values besides 0, e.g. 65535',
+            summary: '{count} anomalies in {sensors} sensors',
+            truncated: ' (only the most severe are listed)',
+            time: 'Time',
+            sensor: 'Sensor',
+            kind: 'Type',
+            severity: 'Severity',
+            value: 'Value',
+            expected: 'Expected',
+            score: 'Score',
+            kinds: {
+                zScore: 'z-score',
+                mad: 'MAD outlier',
+                step: 'Step change',
+                dropout: 'Dropout'
+            },
+            severities: {
+                low: 'Low',
+                medium: 'Medium',
+                high: 'High'
+            }
+        },
+        fanCurve: {
+            title: 'Fan curve',
+            hint: 'Pairs a fan or pump sensor with a temperature, fits a piecewise-linear curve and checks for hysteresis and fan hunting.',
+            fanSensor: 'Fan / pump sensor (RPM or %)',
+            temperatureSensor: 'Temperature sensor',
+            segments: 'Segments',
+            fitted: 'Fitted curve',
+            risingCurve: 'Heating up',
+            fallingCurve: 'Cooling down',
+            trends: {
+                rising: 'Rising',
+                falling: 'Falling',
+                steady: 'Steady'
+            },
+            rmse: 'fit error (RMSE) {rmse}',
+            hysteresis: 'Hysteresis: the fan runs {gap} higher while cooling down (about {offset} °C)',
+            noHysteresis: 'No significant hysteresis',
+            hysteresisUnknown: 'Not enough heating and cooling samples to estimate hysteresis',
+            oscillation: 'Fan hunting detected in {count} episode(s)',
+            noOscillation: 'No fan hunting (swings of at least {threshold})',
+            start: 'Start',
+            duration: 'Duration',
+            cycles: 'Cycles',
+            period: 'Period',
+            amplitude: 'Swing',
+            temperatureSpan: 'Temp. range'
+        },
+        thermal: {
+            title: 'Heat soak',
+            hint: 'Fits a first-order response to a temperature after a load step: time constant, steady-state value and time to 95 %.',
+            sensor: 'Temperature sensor',
+            loadSensor: 'Load sensor (automatic when empty)',
+            autoStart: 'Load step (auto)',
+            autoEnd: 'End (auto)',
+            detected: 'Load step detected on {sensor} at {start}',
+            timeConstant: 'Time constant',
+            timeTo95: 'Time to 95 %',
+            asymptote: 'Steady state',
+            initial: 'Initial',
+            notReached: 'The window ends before 95 % of the step; the steady state is extrapolated.',
+            measured: 'Measured',
+            fitted: 'Fit'
+        },
+        energy: {
+            title: 'Energy',
+            hint: 'Integrates power sensors over the log (trapezoidal, using the actual sample times). Pauses in logging are not bridged.',
+            sensorsPlaceholder: 'Power sensors (CPU package and GPU power when empty)',
+            byPhase: 'Break down by load phase',
+            total: 'Total energy',
+            joules: 'Energy',
+            averagePower: 'Average power',
+            sensor: 'Sensor',
+            covered: 'Integrated time',
+            phaseHint: 'Phases detected from {sensor}'
+        },
+        phases: {
+            title: 'Load phases',
+            hint: 'Splits the log into idle, light, full-load and cooldown phases from a utilization or power sensor.',
+            autoSensor: 'Load sensor (automatic when empty)',
+            auto: 'Auto',
+            idleBelow: 'Idle <',
+            loadAbove: 'Full ≥',
+            statsPlaceholder: 'Sensors to summarize per phase',
+            thresholds: 'Segmented by {sensor}: idle below {idle}, full load from {load}',
+            kind: 'Phase',
+            start: 'Start',
+            duration: 'Duration',
+            meanMax: 'mean / max',
+            kinds: {
+                idle: 'Idle',
+                light: 'Light',
+                fullLoad: 'Full load',
+                cooldown: 'Cooldown'
+            }
+        },
+        histogram: {
+            title: 'Distribution',
+            sensor: 'Sensor',
+            bins: 'Bins',
+            autoBins: 'Auto',
+            edgesPlaceholder: 'Custom bin edges, e.g. 40, 60, 80, 100',
+            byTime: 'Time residency',
+            byCount: 'Sample count',
+            outside: 'Outside the bins: {below} below, {above} above'
+        },
+        compare: {
+            title: 'Compare two runs',
+            hint: 'Aligns run B to baseline run A on elapsed time (or a sync event such as load start) and pairs sensors by name. Δ = B − A; p from the Welch t-test.',
+            baseline: 'Baseline (A)',
+            candidate: 'Candidate (B)',
+            alignStart: 'From log start',
+            alignSync: 'Sync event',
+            syncSensor: 'Sync sensor',
+            sensorsPlaceholder: 'Sensors to compare (all paired sensors when empty)',
+            sensor: 'Sensor',
+            significant: 'Significant',
+            yes: 'Yes',
+            no: 'No',
+            noResample: 'No resampling',
+            resampleEvery: 'Resample to {s} s',
+            unmatched: '{a} sensors only in A, {b} only in B'
+        },
+        correlation: {
+            matrixTitle: 'Correlation matrix',
+            pairs: '{n} overlapping samples',
+            lagTitle: 'Lag (cross-correlation)',
+            lagHint: 'Estimates how long the follower trails the leader, e.g. CPU power → package temperature → fan speed.',
+            leader: 'Leader sensor',
+            follower: 'Follower sensor',
+            maxLag: 'Max lag',
+            lagResult: 'Best match at a lag of {lag} (r = {r})',
+            noLag: 'Not enough overlapping samples'
+        }
+    },
+    throttling: {
+        title: 'Throttling & limiters',
+        hint: 'HWiNFO [Yes/No] throttling and performance-limit flags, merged per device.',
+        refresh: 'Refresh',
+        device: 'Device',
+        throttledTime: 'Throttled time',
+        throttledRatio: 'Share of log',
+        longest: 'Longest episode',
+        firstLimiter: 'First limiter',
+        flag: 'Flag',
+        episodes: 'Episodes',
+        firstAt: 'First set at',
+        none: 'None',
+        noFlags: 'This log has no throttling flags',
+        loadFailed: 'Failed to analyze throttling: {error}'
     },
     sidebar: {
+        searchPlaceholder: 'Search sensors (pinyin supported)',
         loadingData: 'Loading data…',
         noDataHint: 'No data. Please load and process a CSV first.',
         dataLoaded: 'Data loaded',
         dataLoadFailed: 'Failed to load data: {error}',
-        parseBackendFailed: 'Failed to parse backend response: {error}'
+        configReloaded: 'Grouping config reloaded; reload the CSV to apply it',
+        parseError: 'Log parsing failed: {error}',
+        thresholdEpisode: '{sensor} crossed its threshold for {seconds} s (peak {peak})'
     },
     chart: {
         analysisTitle: 'Statistics',
@@ -68,6 +282,22 @@ export default {
         max: 'Max',
         avg: 'Average',
         median: 'Median',
+        stddev: 'Std. deviation',
+        p99: 'P99',
+        filter: 'Filter',
+        filter_none: 'None',
+        filter_sma: 'Moving average',
+        filter_ema: 'Exponential (EMA)',
+        filter_median: 'Median',
+        smoothedSeriesName: '{name} (smoothed)',
+        exportSeries: 'Export series',
+        exportRaw: 'Raw samples',
+        exportEvery: 'Every {s} s',
+        exportSeriesSuccess: 'Series exported to {path}',
+        exportSeriesFailed: 'Failed to export series: {error}',
+        exportStats: 'Export stats',
+        exportStatsSuccess: 'Statistics exported to {path}',
+        exportStatsFailed: 'Failed to export statistics: {error}',
         trendTitle: 'Trend',
         resetView: 'Reset view',
 
@@ -101,10 +331,8 @@ export default {
         emptyHint: 'Select a sensor on the left to view data',
 
         loadingSensorData: 'Loading "{name}"…',
-        parseSensorDataFailed: 'Failed to parse "{name}": {error}',
         loadedSensorDataSuccess: 'Loaded "{name}" ({count} rows)',
         loadSensorDataFailed: 'Failed to load "{name}": {error}',
-        unrecognizedField: 'Failed to recognize the sensor field. The backend response format may have changed.',
         valueSeriesName: 'Value',
 
         seriesBelowMin: '{name} (below min)',
@@ -16,7 +16,9 @@ export default {
     nav: {
         home: '首页',
         sensorData: '传感器数据',
-        settings: '系统设置'
+        settings: '系统设置',
+        analysis: '数据分析',
+        throttling: '降频分析'
     },
     dashboard: {
         welcomeTitle: '欢迎使用 HWInfo Log Viewer',
@@ -33,6 +35,10 @@ export default {
         processingCsv: '正在处理 CSV…',
         csvProcessDone: 'CSV 处理完成',
         csvProcessFailed: '处理失败：{error}',
+        mergeCsv: '合并多个日志…',
+        mergingCsv: '正在合并 {count} 个日志…',
+        mergeDone: '已将 {count} 个日志合并为一个数据集',
+        mergeFailed: '合并失败：{error}',
         selectCsvFailed: '选择 CSV 文件失败：{error}',
         selectCsvSuccess: '已选择 CSV：{path}'
     },
@@ -52,14 +58,222 @@ export default {
         csvProcessFailed: 'CSV 处理失败：{error}',
         selectCsvFailed: '选择 CSV 文件失败：{error}',
         selectCsvSuccess: '已选择 CSV：{path}',
-        dataCleared: '数据已清除'
+        dataCleared: '数据已清除',
+
+        watchFolder: '监听日志目录',
+        stopWatchFolder: '停止监听',
+        watchingFolder: '正在监听目录（自动打开最新日志）',
+        watchFolderOpened: '已打开最新日志：{path}',
+        watchFolderWaiting: '目录中暂无 HWiNFO 日志，等待新日志生成',
+        watchFolderFailed: '监听目录失败：{error}'
+    },
+    derived: {
+        title: '派生传感器',
+        hint: '基于已有传感器计算新传感器，例如 `CPU Package Power [W]` + `GPU Power [W]`。传感器名称用反引号包裹；可用函数：abs、sqrt、round、floor、ceil、min、max、avg、sum、pow、clamp、if。',
+        namePlaceholder: '名称，例如 整机功耗 [W]',
+        exprPlaceholder: '表达式',
+        groupPlaceholder: '分组路径，例如 系统/功耗（默认 Derived）',
+        add: '添加',
+        remove: '删除',
+        fromConfig: '来自 groups.toml',
+        added: '已添加派生传感器「{name}」',
+        addFailed: '添加派生传感器失败：{error}',
+        removeFailed: '删除派生传感器失败：{error}'
+    },
+    analysis: {
+        correlationTab: '相关性',
+        histogramTab: '分布直方图',
+        compareTab: 'A/B 对比',
+        aggregateTab: '时间桶聚合',
+        anomalyTab: '异常检测',
+        phasesTab: '负载阶段',
+        energyTab: '能耗',
+        thermalTab: '热饱和',
+        fanCurveTab: '风扇曲线',
+        sensorsPlaceholder: '选择传感器',
+        compute: '计算',
+        failed: '分析失败：{error}',
+        aggregate: {
+            title: '按时间桶汇总',
+            export: '导出 CSV',
+            seconds: '{n} 秒',
+            minutes: '{n} 分钟',
+            hours: '{n} 小时',
+            bucketStart: '起始时间',
+            rows: '行数',
+            mean: '均值',
+            min: '最小值',
+            max: '最大值',
+            last: '末值',
+            p95: 'P95',
+            exported: '聚合结果已导出到 {path}'
+        },
+        anomaly: {
+            title: '尖峰与异常',
+            hint: '滚动 z 分数、MAD 离群点、突变和掉线（读数为 0 或哨兵值）。点击一行可跳转到该传感器的对应时刻。',
+            sensorsPlaceholder: '要扫描的传感器（留空则扫描全部数值传感器）',
+            window: '窗口',
+            sentinelsPlaceholder: '除 0 以外的掉线读数，例如 65535',
+            summary: '在 {sensors} 个传感器中发现 {count} 处异常',
+            truncated: '（仅列出最严重的部分）',
+            time: '时间',
+            sensor: '传感器',
+            kind: '类型',
+            severity: '严重程度',
+            value: '数值',
+            expected: '预期',
+            score: '得分',
+            kinds: {
+                zScore: 'z 分数',
+                mad: 'MAD 离群',
+                step: '突变',
+                dropout: '掉线'
+            },
+            severities: {
+                low: '低',
+                medium: '中',
+                high: '高'
+            }
+        },
+        fanCurve: {
+            title: '风扇曲线',
+            hint: '将风扇或水泵传感器与温度配对，拟合分段线性曲线，并检测迟滞和风扇反复升降（喘振）。',
+            fanSensor: '风扇 / 水泵传感器（RPM 或 %）',
+            temperatureSensor: '温度传感器',
+            segments: '分段数',
+            fitted: '拟合曲线',
+            risingCurve: '升温',
+            fallingCurve: '降温',
+            trends: {
+                rising: '升温',
+                falling: '降温',
+                steady: '平稳'
+            },
+            rmse: '拟合误差（RMSE）{rmse}',
+            hysteresis: '存在迟滞：降温时风扇比升温时高 {gap}（约 {offset} °C）',
+            noHysteresis: '无明显迟滞',
+            hysteresisUnknown: '升温或降温样本不足，无法估计迟滞',
+            oscillation: '检测到 {count} 段风扇反复升降',
+            noOscillation: '未检测到风扇反复升降（幅度阈值 {threshold}）',
+            start: '开始',
+            duration: '时长',
+            cycles: '周期数',
+            period: '周期',
+            amplitude: '幅度',
+            temperatureSpan: '温度变化'
+        },
+        thermal: {
+            title: '热饱和',
+            hint: '对负载阶跃后的温度拟合一阶响应，给出时间常数、稳态值和达到 95% 所需时间。',
+            sensor: '温度传感器',
+            loadSensor: '负载传感器（留空自动选择）',
+            autoStart: '负载开始（自动）',
+            autoEnd: '结束（自动）',
+            detected: '在 {sensor} 上检测到负载开始于 {start}',
+            timeConstant: '时间常数',
+            timeTo95: '达到 95% 时间',
+            asymptote: '稳态值',
+            initial: '初始值',
+            notReached: '所选时间段在达到 95% 之前结束，稳态值为外推结果。',
+            measured: '实测',
+            fitted: '拟合'
+        },
+        energy: {
+            title: '能耗',
+            hint: '按实际采样时间对功率传感器做梯形积分；记录中断的时间段不计入。',
+            sensorsPlaceholder: '功率传感器（留空使用 CPU 封装功率和 GPU 功率）',
+            byPhase: '按负载阶段拆分',
+            total: '总能耗',
+            joules: '能量',
+            averagePower: '平均功率',
+            sensor: '传感器',
+            covered: '积分时长',
+            phaseHint: '阶段由 {sensor} 划分'
+        },
+        phases: {
+            title: '负载阶段',
+            hint: '根据使用率或功率传感器把日志划分为空闲、轻载、满载和冷却阶段。',
+            autoSensor: '负载传感器（留空自动选择）',
+            auto: '自动',
+            idleBelow: '空闲 <',
+            loadAbove: '满载 ≥',
+            statsPlaceholder: '按阶段汇总的传感器',
+            thresholds: '按 {sensor} 分段：低于 {idle} 为空闲，不低于 {load} 为满载',
+            kind: '阶段',
+            start: '开始',
+            duration: '时长',
+            meanMax: '均值 / 最大值',
+            kinds: {
+                idle: '空闲',
+                light: '轻载',
+                fullLoad: '满载',
+                cooldown: '冷却'
+            }
+        },
+        histogram: {
+            title: '数值分布',
+            sensor: '传感器',
+            bins: '分箱数',
+            autoBins: '自动',
+            edgesPlaceholder: '自定义分箱边界，例如 40, 60, 80, 100',
+            byTime: '按停留时间',
+            byCount: '按样本数',
+            outside: '超出分箱范围：低于 {below}，高于 {above}'
+        },
+        compare: {
+            title: '两次运行对比',
+            hint: '按经过时间（或负载开始等同步事件）将运行 B 与基准运行 A 对齐，并按名称配对传感器。Δ = B − A，p 值来自 Welch t 检验。',
+            baseline: '基准 (A)',
+            candidate: '对比 (B)',
+            alignStart: '从日志开始',
+            alignSync: '同步事件',
+            syncSensor: '同步传感器',
+            sensorsPlaceholder: '要对比的传感器（留空则对比全部配对传感器）',
+            sensor: '传感器',
+            significant: '显著',
+            yes: '是',
+            no: '否',
+            noResample: '不重采样',
+            resampleEvery: '重采样到 {s} 秒',
+            unmatched: '仅 A 有 {a} 个传感器，仅 B 有 {b} 个'
+        },
+        correlation: {
+            matrixTitle: '相关系数矩阵',
+            pairs: '重叠样本 {n} 个',
+            lagTitle: '滞后分析（互相关）',
+            lagHint: '估算跟随传感器相对领先传感器的延迟，例如 CPU 功率 → 封装温度 → 风扇转速。',
+            leader: '领先传感器',
+            follower: '跟随传感器',
+            maxLag: '最大滞后',
+            lagResult: '滞后 {lag} 时相关性最强（r = {r}）',
+            noLag: '重叠样本不足'
+        }
+    },
+    throttling: {
+        title: '降频与性能受限',
+        hint: '按设备汇总 HWiNFO 中 [Yes/No] 类型的降频与性能受限标志。',
+        refresh: '刷新',
+        device: '设备',
+        throttledTime: '受限时长',
+        throttledRatio: '占日志比例',
+        longest: '最长一次',
+        firstLimiter: '最先触发',
+        flag: '标志',
+        episodes: '次数',
+        firstAt: '首次触发时间',
+        none: '无',
+        noFlags: '该日志中没有降频标志',
+        loadFailed: '降频分析失败：{error}'
     },
     sidebar: {
+        searchPlaceholder: '搜索传感器（支持拼音）',
         loadingData: '正在获取数据…',
         noDataHint: '暂无数据，请先上传并处理 CSV',
         dataLoaded: '数据加载成功',
         dataLoadFailed: '获取数据失败：{error}',
-        parseBackendFailed: '解析后端返回失败：{error}'
+        configReloaded: '分组配置已重新加载，重新处理 CSV 后生效',
+        parseError: '日志解析失败：{error}',
+        thresholdEpisode: '{sensor} 超出阈值持续 {seconds} 秒（峰值 {peak}）'
     },
     chart: {
         analysisTitle: '数据分析',
@@ -68,6 +282,22 @@ export default {
         max: '最大值',
         avg: '平均值',
         median: '中位数',
+        stddev: '标准差',
+        p99: 'P99',
+        filter: '滤波',
+        filter_none: '无',
+        filter_sma: '移动平均',
+        filter_ema: '指数平滑 (EMA)',
+        filter_median: '中值滤波',
+        smoothedSeriesName: '{name}（平滑）',
+        exportSeries: '导出序列',
+        exportRaw: '原始采样',
+        exportEvery: '每 {s} 秒',
+        exportSeriesSuccess: '序列已导出到 {path}',
+        exportSeriesFailed: '导出序列失败：{error}',
+        exportStats: '导出统计',
+        exportStatsSuccess: '统计已导出到 {path}',
+        exportStatsFailed: '导出统计失败：{error}',
         trendTitle: '趋势图',
         resetView: '重置视图',
 
@@ -101,10 +331,8 @@ export default {
         emptyHint: '请选择左侧传感器查看数据',
 
         loadingSensorData: '正在加载「{name}」数据…',
-        parseSensorDataFailed: '解析「{name}」数据失败：{error}',
         loadedSensorDataSuccess: '已加载「{name}」数据（{count} 条）',
         loadSensorDataFailed: '加载「{name}」数据失败：{error}',
-        unrecognizedField: '未能识别该传感器字段，可能是后端返回结构变化。',
         valueSeriesName: '数值',
 
         seriesBelowMin: '{name}（低于下限）',
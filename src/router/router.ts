@@ -20,6 +20,16 @@ const router = createRouter({
                     // send fieldKey to SensorChart.vue
                     props: true
                 },
+                {
+                    path: '/analysis',
+                    name: 'Analysis',
+                    component: () => import('../views/AnalysisView.vue')
+                },
+                {
+                    path: '/throttling',
+                    name: 'Throttling',
+                    component: () => import('../views/ThrottlingView.vue')
+                },
                 {
                     path: '/settings',
                     name: 'Settings',
@@ -7,6 +7,12 @@ import { loadPersistedLocale, persistLocale } from '../utils/locale'
 export const useAppStore = defineStore('app', () => {
   const filePath = ref<string | null>(null)
   const isLoaded = ref(false)
+  // Backend dataset currently shown (see `load_csv` / `list_datasets`)
+  const datasetId = ref<number | null>(null)
+  // Bumped whenever the active dataset is (re)loaded so views can refetch
+  const dataVersion = ref(0)
+  // Folder being watched for new HWiNFO logs (null when not watching)
+  const watchFolder = ref<string | null>(null)
 
   const locale = ref<AppLocale>(loadPersistedLocale('zh-CN'))
 
@@ -25,9 +31,22 @@ export const useAppStore = defineStore('app', () => {
     isLoaded.value = loaded
   }
 
+  function setDatasetId(id: number | null) {
+    datasetId.value = id
+  }
+
+  function bumpDataVersion() {
+    dataVersion.value += 1
+  }
+
+  function setWatchFolder(dir: string | null) {
+    watchFolder.value = dir
+  }
+
   function clearData() {
     filePath.value = null
     isLoaded.value = false
+    datasetId.value = null
   }
 
   function setLocale(next: AppLocale) {
@@ -37,9 +56,15 @@ export const useAppStore = defineStore('app', () => {
   return {
     filePath,
     isLoaded,
+    datasetId,
+    dataVersion,
+    watchFolder,
     locale,
     setFilePath,
     setLoaded,
+    setDatasetId,
+    bumpDataVersion,
+    setWatchFolder,
     setLocale,
     clearData
   }
@@ -3,6 +3,7 @@ import { ref, watch } from 'vue'
 
 export type SamplingMode = 'auto' | 'none' | 'lttb' | 'average' | 'max' | 'min'
 export type YAxisScaleType = 'linear' | 'log'
+export type FilterMode = 'none' | 'sma' | 'ema' | 'median'
 
 const STORAGE_KEY = 'hwinfo-log-viewer:chart-prefs:v1'
 
@@ -21,6 +22,9 @@ export const useChartPrefsStore = defineStore('chartPrefs', () => {
   const connectNulls = ref(false)
   const sampling = ref<SamplingMode>('auto')
   const yAxisScale = ref<YAxisScaleType>('linear')
+  // 后端平滑滤波，叠加在原始曲线上；窗口（EMA 为时间常数）单位为秒
+  const filter = ref<FilterMode>('none')
+  const filterWindowS = ref(10)
 
   // Threshold warning
   const warnEnabled = ref(false)
@@ -35,6 +39,8 @@ export const useChartPrefsStore = defineStore('chartPrefs', () => {
     if (typeof persisted.connectNulls === 'boolean') connectNulls.value = persisted.connectNulls
     if (typeof persisted.sampling === 'string') sampling.value = persisted.sampling
     if (typeof persisted.yAxisScale === 'string') yAxisScale.value = persisted.yAxisScale
+    if (typeof persisted.filter === 'string') filter.value = persisted.filter
+    if (typeof persisted.filterWindowS === 'number' && persisted.filterWindowS > 0) filterWindowS.value = persisted.filterWindowS
 
     if (typeof persisted.warnEnabled === 'boolean') warnEnabled.value = persisted.warnEnabled
     if (persisted.warnMin == null) warnMin.value = null
@@ -50,6 +56,8 @@ export const useChartPrefsStore = defineStore('chartPrefs', () => {
       connectNulls: connectNulls.value,
       sampling: sampling.value,
       yAxisScale: yAxisScale.value,
+      filter: filter.value,
+      filterWindowS: filterWindowS.value,
       warnEnabled: warnEnabled.value,
       warnMin: warnMin.value,
       warnMax: warnMax.value
@@ -66,6 +74,8 @@ export const useChartPrefsStore = defineStore('chartPrefs', () => {
     connectNulls.value = false
     sampling.value = 'auto'
     yAxisScale.value = 'linear'
+    filter.value = 'none'
+    filterWindowS.value = 10
 
     warnEnabled.value = false
     warnMin.value = null
@@ -78,6 +88,8 @@ export const useChartPrefsStore = defineStore('chartPrefs', () => {
     connectNulls,
     sampling,
     yAxisScale,
+    filter,
+    filterWindowS,
     warnEnabled,
     warnMin,
     warnMax,
@@ -0,0 +1,83 @@
+// Event names and payloads emitted by the backend (see src-tauri/src/events.rs).
+
+export const DATASET_LOADED = 'dataset-loaded'
+export const DATASET_CLOSED = 'dataset-closed'
+export const ROWS_APPENDED = 'rows-appended'
+export const CONFIG_RELOADED = 'config-reloaded'
+export const PARSE_ERROR = 'parse-error'
+export const THRESHOLD_EPISODE = 'threshold-episode'
+
+// One processed CSV row: top-level group name -> nested group data.
+export type DataRow = Record<string, any>
+
+export type DatasetLoadedPayload = {
+  datasetId: number
+  path: string
+  rowCount: number
+}
+
+export type DatasetClosedPayload = {
+  datasetId: number
+  path: string
+}
+
+export type RowsAppendedPayload = {
+  datasetId: number
+  path: string
+  startIndex: number
+  rows: DataRow[]
+}
+
+export type ConfigReloadedPayload = {
+  path: string
+}
+
+export type ParseErrorPayload = {
+  datasetId: number | null
+  path: string
+  message: string
+}
+
+export type Episode = {
+  sensor: string
+  start: number
+  end: number
+  durationMs: number
+  peak: number
+  peakAt: number
+  samples: number
+}
+
+export type ThresholdEpisodePayload = {
+  datasetId: number
+  episode: Episode
+}
+
+// Mirror of backend `find_key_in_group`: flatten rows into `{...base, [key]: value}` items,
+// one item per occurrence of `key`, so appended rows can reuse the chart's point builder.
+export function flattenRowsForKey(rows: DataRow[], key: string): Record<string, string>[] {
+  const out: Record<string, string>[] = []
+
+  const search = (group: Record<string, any>, base: Record<string, string>) => {
+    for (const value of Object.values(group)) {
+      if (!value || typeof value !== 'object') continue
+      const raw = value[key]
+      if (raw != null && typeof raw !== 'object') {
+        out.push({...base, [key]: String(raw)})
+      }
+      if (value.children) search(value.children, base)
+    }
+  }
+
+  for (const row of rows) {
+    const base: Record<string, string> = {}
+    const baseGroup = row?.base
+    if (baseGroup && typeof baseGroup === 'object') {
+      for (const [k, v] of Object.entries(baseGroup)) {
+        if (typeof v !== 'object') base[k] = String(v)
+      }
+    }
+    search(row ?? {}, base)
+  }
+  return out
+}
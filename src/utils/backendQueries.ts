@@ -0,0 +1,536 @@
+// Request/response types of the backend dataset query commands.
+import type {DataRow} from './backendEvents'
+
+export type RowQuery = {
+  start?: number
+  end?: number
+  sensors?: string[]
+  offset?: number
+  limit?: number
+}
+
+export type RowPage = {
+  total: number
+  offset: number
+  rows: DataRow[]
+}
+
+export type TimeBounds = {
+  start: number
+  end: number
+  rowCount: number
+}
+
+export type Downsample = 'lttb' | 'minMax'
+
+// 窗口与时间常数单位为 ms；SMA/中值为居中窗口
+export type Smoothing =
+    | {method: 'sma'; windowMs: number}
+    | {method: 'ema'; tauMs: number}
+    | {method: 'median'; windowMs: number}
+
+export type ResampleMethod = 'nearest' | 'linear' | 'previous' | 'mean'
+export type GapFill = 'empty' | 'previous' | 'linear' | 'zero'
+
+// 固定间隔重采样；maxGapMs 缺省为 3 个间隔（或 3 个原始采样间隔）
+export type ResampleSpec = {
+  intervalMs: number
+  method?: ResampleMethod
+  maxGapMs?: number
+  fill?: GapFill
+}
+
+export type SeriesQuery = {
+  sensors: string[]
+  start?: number
+  end?: number
+  maxPoints?: number
+  downsample?: Downsample
+  resample?: ResampleSpec
+  // 与 sensors 按位置对应，null 表示原始值；同一传感器可出现两次以同时取原始与平滑曲线
+  smoothing?: (Smoothing | null)[]
+  // 与 sensors 按位置对应的分组路径，用于区分多个设备上的同名传感器
+  paths?: (string[] | null)[]
+}
+
+export type SensorSeries = {
+  sensor: string
+  values: (number | null)[]
+  smoothing: Smoothing | null
+}
+
+// Parallel arrays: series[i].values[j] was sampled at timestamps[j].
+export type SeriesSet = {
+  timestamps: number[]
+  series: SensorSeries[]
+  skippedRows: number
+  sourceCount: number
+}
+
+export type StatsQuery = {
+  sensors: string[]
+  start?: number
+  end?: number
+  // 0-100; backend default is [1, 5, 25, 75, 95, 99]
+  percentiles?: number[]
+  resample?: ResampleSpec
+}
+
+export type SensorStats = {
+  sensor: string
+  count: number
+  missing: number
+  min: number | null
+  minAt: number | null
+  max: number | null
+  maxAt: number | null
+  mean: number | null
+  median: number | null
+  stddev: number | null
+  percentiles: {p: number; value: number}[]
+}
+
+export type StatsTable = {
+  start: number | null
+  end: number | null
+  percentiles: number[]
+  rows: SensorStats[]
+}
+
+export type SearchMode = 'substring' | 'regex' | 'fuzzy'
+
+export type SearchQuery = {
+  query: string
+  mode?: SearchMode
+  limit?: number
+}
+
+export type SearchHit = {
+  sensor: string
+  path: string[]
+  score: number
+  matchedBy: 'exact' | 'prefix' | 'substring' | 'pinyin' | 'regex' | 'fuzzy'
+}
+
+export type ValueType = 'numeric' | 'boolean' | 'text'
+
+export type SensorInfo = {
+  sensor: string
+  path: string[]
+  column: number | null
+  unit: string | null
+  valueType: ValueType
+  samples: number
+  missingRatio: number
+  min: number | null
+  max: number | null
+}
+
+export type DerivedSensor = {
+  name: string
+  // Sensor names are quoted with backticks, e.g. `CPU Package Power [W]` + `GPU Power [W]`
+  expr: string
+  group: string[]
+}
+
+export type ThresholdRule = {
+  sensor: string
+  op: 'gt' | 'ge' | 'lt' | 'le'
+  threshold: number
+  minDurationMs?: number
+  maxGapMs?: number
+}
+
+export type ThrottlingQuery = {
+  start?: number
+  end?: number
+  // Defaults to the recognized throttling / limiter flags
+  sensors?: string[]
+}
+
+export type FlagInterval = {
+  start: number
+  end: number
+  durationMs: number
+}
+
+export type FlagSummary = {
+  sensor: string
+  path: string[]
+  intervals: FlagInterval[]
+  throttledMs: number
+  throttledRatio: number
+  longest: FlagInterval | null
+  firstAt: number | null
+}
+
+export type DeviceThrottling = {
+  device: string
+  flags: FlagSummary[]
+  throttledMs: number
+  throttledRatio: number
+  longest: FlagInterval | null
+  firstLimiter: string | null
+}
+
+export type ThrottlingReport = {
+  start: number | null
+  end: number | null
+  spanMs: number
+  devices: DeviceThrottling[]
+}
+
+export type CorrelationMethod = 'pearson' | 'spearman'
+
+export type CorrelationQuery = {
+  sensors: string[]
+  start?: number
+  end?: number
+  method?: CorrelationMethod
+}
+
+export type CorrelationMatrix = {
+  sensors: string[]
+  method: CorrelationMethod
+  // null where there are too few overlapping samples or a sensor is constant
+  matrix: (number | null)[][]
+  samples: number[][]
+}
+
+export type LagQuery = {
+  leader: string
+  follower: string
+  start?: number
+  end?: number
+  maxLagMs?: number
+}
+
+export type LagResult = {
+  leader: string
+  follower: string
+  stepMs: number
+  // Positive: the follower reacts later than the leader
+  bestLagMs: number | null
+  bestCorrelation: number | null
+  lags: {lagMs: number; correlation: number}[]
+}
+
+export type Weighting = 'time' | 'count'
+
+export type HistogramQuery = {
+  sensor: string
+  start?: number
+  end?: number
+  bins?: number
+  // Strictly increasing; takes precedence over bins
+  edges?: number[]
+  weighting?: Weighting
+}
+
+export type HistogramBin = {
+  // null for the open-ended underflow / overflow bins
+  lower: number | null
+  upper: number | null
+  count: number
+  durationMs: number
+  share: number
+}
+
+export type Histogram = {
+  sensor: string
+  weighting: Weighting
+  bins: HistogramBin[]
+  underflow: HistogramBin
+  overflow: HistogramBin
+  totalCount: number
+  totalMs: number
+  min: number | null
+  max: number | null
+}
+
+// Pause between two logs of a merged dataset
+export type DataGap = {
+  start: number
+  end: number
+  durationMs: number
+  before: string
+  after: string
+}
+
+export type DatasetInfo = {
+  id: number
+  path: string
+  rowCount: number
+  loadedAt: number
+}
+
+export type Alignment =
+  | {mode: 'start'}
+  // e.g. CPU load > 80 % marks the start of the load phase
+  | {mode: 'syncEvent'; sensor: string; op: ThresholdRule['op']; threshold: number}
+
+export type CompareQuery = {
+  alignment?: Alignment
+  durationMs?: number
+  sensors?: string[]
+  includeSeries?: boolean
+  maxPoints?: number
+  resample?: ResampleSpec
+}
+
+export type WelchTest = {
+  t: number
+  df: number
+  pValue: number
+}
+
+export type ElapsedSeries = {
+  elapsedMs: number[]
+  values: (number | null)[]
+}
+
+export type SensorComparison = {
+  sensorA: string
+  sensorB: string
+  // 分组路径，区分同名传感器所在的设备
+  pathA: string[]
+  pathB: string[]
+  a: SensorStats
+  b: SensorStats
+  // Candidate minus baseline
+  deltaMean: number | null
+  deltaPercent: number | null
+  welch: WelchTest | null
+  significant: boolean
+  seriesA: ElapsedSeries | null
+  seriesB: ElapsedSeries | null
+}
+
+export type ComparisonReport = {
+  originA: number
+  originB: number
+  durationMs: number
+  sensors: SensorComparison[]
+  unmatchedA: string[]
+  unmatchedB: string[]
+}
+
+export type Aggregator = 'mean' | 'min' | 'max' | 'last' | 'p95'
+
+export type AggregateQuery = {
+  sensors: string[]
+  start?: number
+  end?: number
+  bucketMs: number
+  // 缺省为 ['mean']
+  aggregators?: Aggregator[]
+}
+
+export type AggregateColumn = {
+  sensor: string
+  aggregator: Aggregator
+}
+
+export type AggregateBucket = {
+  start: number
+  end: number
+  rows: number
+  // 与 AggregateTable.columns 一一对应
+  values: (number | null)[]
+}
+
+export type AggregateTable = {
+  bucketMs: number
+  columns: AggregateColumn[]
+  buckets: AggregateBucket[]
+}
+
+export type AnomalyQuery = {
+  // 缺省时扫描全部数值传感器
+  sensors?: string[]
+  start?: number
+  end?: number
+  windowMs?: number
+  zThreshold?: number
+  madThreshold?: number
+  stepThreshold?: number
+  // 除 0 以外表示掉线的读数，例如 65535
+  sentinels?: number[]
+  maxResults?: number
+}
+
+export type AnomalyKind = 'zScore' | 'mad' | 'step' | 'dropout'
+export type AnomalySeverity = 'low' | 'medium' | 'high'
+
+export type Anomaly = {
+  sensor: string
+  path: string[]
+  kind: AnomalyKind
+  severity: AnomalySeverity
+  // 峰值时刻与数值
+  timestamp: number
+  value: number
+  expected: number
+  score: number
+  start: number
+  end: number
+}
+
+export type AnomalyReport = {
+  anomalies: Anomaly[]
+  scannedSensors: number
+  truncated: boolean
+}
+
+export type PhaseQuery = {
+  // 缺省时自动选择 CPU 总使用率 / 封装功率等负载传感器
+  sensor?: string
+  start?: number
+  end?: number
+  idleBelow?: number
+  loadAbove?: number
+  minDurationMs?: number
+  smoothingMs?: number
+  cooldownMs?: number
+  statsSensors?: string[]
+}
+
+export type PhaseKind = 'idle' | 'light' | 'fullLoad' | 'cooldown'
+
+export type Phase = {
+  kind: PhaseKind
+  start: number
+  end: number
+  durationMs: number
+  // 第一个为分段所用的传感器
+  stats: SensorStats[]
+}
+
+export type PhaseReport = {
+  sensor: string
+  idleBelow: number
+  loadAbove: number
+  phases: Phase[]
+}
+
+export type EnergyQuery = {
+  // 为空时使用 CPU 封装功率 / GPU 功率
+  sensors?: string[]
+  start?: number
+  end?: number
+  maxGapMs?: number
+  phases?: PhaseQuery
+}
+
+export type Energy = {
+  joules: number
+  wattHours: number
+  averageW: number | null
+  coveredMs: number
+}
+
+export type SensorEnergy = Energy & { sensor: string }
+
+export type PhaseEnergy = {
+  kind: PhaseKind
+  start: number
+  end: number
+  durationMs: number
+  total: Energy
+  sensors: Energy[]
+}
+
+export type EnergyReport = {
+  start: number | null
+  end: number | null
+  sensors: SensorEnergy[]
+  total: Energy
+  phaseSensor: string | null
+  phases: PhaseEnergy[]
+}
+
+export type ThermalFitQuery = {
+  sensor: string
+  // 省略 start 时使用负载传感器的第一个满载阶段
+  start?: number
+  end?: number
+  loadSensor?: string
+  maxPoints?: number
+}
+
+export type FitPoint = {
+  timestamp: number
+  measured: number
+  fitted: number
+}
+
+export type ThermalFit = {
+  sensor: string
+  start: number
+  end: number
+  loadSensor: string | null
+  initial: number
+  asymptote: number
+  timeConstantS: number
+  timeTo95S: number
+  reached95: boolean
+  rSquared: number
+  rmse: number
+  samples: number
+  curve: FitPoint[]
+}
+
+export type FanCurveQuery = {
+  fanSensor: string
+  temperatureSensor: string
+  start?: number
+  end?: number
+  segments?: number
+  maxPoints?: number
+  minAmplitude?: number
+  maxPeriodMs?: number
+  minCycles?: number
+}
+
+export type FanTrend = 'rising' | 'falling' | 'steady'
+
+export type FanCurvePoint = {
+  timestamp: number
+  temperature: number
+  fan: number
+  trend: FanTrend
+}
+
+export type PiecewiseFit = {
+  breakpoints: {temperature: number; fan: number}[]
+  rmse: number
+}
+
+export type Hysteresis = {
+  // 降温时与升温时同一温度下的风扇差值
+  fanGap: number
+  temperatureOffset: number | null
+  detected: boolean
+}
+
+export type OscillationEpisode = {
+  start: number
+  end: number
+  cycles: number
+  meanPeriodMs: number
+  amplitude: number
+  temperatureSpan: number
+}
+
+export type FanCurveReport = {
+  fanSensor: string
+  temperatureSensor: string
+  samples: number
+  points: FanCurvePoint[]
+  curve: PiecewiseFit
+  risingCurve: PiecewiseFit | null
+  fallingCurve: PiecewiseFit | null
+  hysteresis: Hysteresis | null
+  oscillationThreshold: number
+  oscillations: OscillationEpisode[]
+}
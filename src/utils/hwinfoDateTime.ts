@@ -99,3 +99,14 @@ export function formatTimeTick(ms: number, spanMs: number): string {
 
   return `${hh}:${mm}`;
 }
+
+export function formatDuration(ms: number): string {
+  const sign = ms < 0 ? '-' : '';
+  const seconds = Math.round(Math.abs(ms) / 1000);
+  const h = Math.floor(seconds / 3600);
+  const m = Math.floor((seconds % 3600) / 60);
+  const s = seconds % 60;
+  if (h > 0) return `${sign}${h}h ${m}m ${s}s`;
+  if (m > 0) return `${sign}${m}m ${s}s`;
+  return `${sign}${s}s`;
+}
@@ -0,0 +1,25 @@
+import {invoke} from '@tauri-apps/api/core'
+import type {SensorInfo} from './backendQueries'
+
+export type SensorOption = {
+  label: string
+  value: string
+}
+
+// 侧边栏菜单 key（与 SideBar 的 convertToMenuOptions 保持一致），同时作为传感器详情页的路由参数
+export function sensorMenuKey(path: string[], sensor: string) {
+  return ['sensor', ...path, sensor].join('-').replace(/\s+/g, '_')
+}
+
+// 分析页的传感器下拉选项：只保留可绘图（数值 / 是否）的传感器，同名传感器只取第一个
+export async function fetchChartableSensors(datasetId: number): Promise<SensorOption[]> {
+  const catalog = await invoke<SensorInfo[]>('get_sensor_catalog', {datasetId})
+  const seen = new Set<string>()
+  const options: SensorOption[] = []
+  for (const info of catalog) {
+    if (info.valueType === 'text' || seen.has(info.sensor)) continue
+    seen.add(info.sensor)
+    options.push({label: `${info.sensor} · ${info.path.join(' / ')}`, value: info.sensor})
+  }
+  return options
+}